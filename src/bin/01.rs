@@ -1,5 +1,4 @@
-use std::collections::HashMap;
-
+use advent_of_code::counter::Counter;
 use itertools::Itertools;
 
 advent_of_code::solution!(1);
@@ -14,20 +13,31 @@ pub fn part_one(input: &str) -> Option<u32> {
         right.push(r.parse::<i32>().ok()?);
     }
 
-    Some(left.iter().sorted().zip(right.iter().sorted()).map(|(r, l)| (r - l).abs()).sum::<i32>() as u32)
+    Some(
+        left.iter()
+            .sorted()
+            .zip(right.iter().sorted())
+            .map(|(r, l)| (r - l).abs())
+            .sum::<i32>() as u32,
+    )
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
-    let mut right = HashMap::new();
+    let mut right = Counter::new();
     let mut left = Vec::new();
 
     for line in input.lines() {
-        let (l, r) = line.split_once("   ").map(|(l, r)| (l.parse::<u32>().ok().unwrap(), r.parse::<u32>().ok().unwrap()))?;
-        left.push(l);
-        right.insert(r, right.get(&r).unwrap_or(&0u32) + 1);
+        let (l, r) = line.split_once("   ")?;
+        left.push(l.parse::<u32>().ok()?);
+        right.add(r.parse::<u32>().ok()?, 1);
     }
 
-    Some(left.iter().map(|l| right.get(l).unwrap_or(&0) * l).sum())
+    u32::try_from(
+        left.iter()
+            .map(|l| right.get(l) * u64::from(*l))
+            .sum::<u64>(),
+    )
+    .ok()
 }
 
 #[cfg(test)]
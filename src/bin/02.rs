@@ -1,16 +1,18 @@
 use itertools::Itertools;
 
-advent_of_code::solution!(2);
+advent_of_code::solution!(2, variants: [part_two_brute_force: 2]);
 
 fn is_safe_1(line: &str) -> bool {
-    let x = line.split_whitespace()
+    let x = line
+        .split_whitespace()
         .map(|n| n.parse::<i32>().unwrap())
         .tuple_windows()
         .map(|(a, b)| ((a - b).abs(), a < b))
         .collect_vec();
 
-    x.iter().all(|(diff, _)| 1 <= *diff && *diff <= 3) &&
-    (x.iter().all(|(_, increasing)| *increasing) || x.iter().all(|(_, increasing)| !increasing))
+    x.iter().all(|(diff, _)| 1 <= *diff && *diff <= 3)
+        && (x.iter().all(|(_, increasing)| *increasing)
+            || x.iter().all(|(_, increasing)| !increasing))
 }
 
 fn is_safe(difference: i32, is_increasing: bool) -> bool {
@@ -21,12 +23,16 @@ fn is_safe(difference: i32, is_increasing: bool) -> bool {
     }
 }
 
-fn is_safe_list(values: &Vec<i32>, is_increasing: bool, skip_index: Option<usize>) -> bool {
+fn is_safe_list(values: &[i32], is_increasing: bool, skip_index: Option<usize>) -> bool {
     for i in 0..values.len() {
         if skip_index == Some(i) {
             continue;
         }
-        let next_index = if skip_index == Some(i+1) {  i + 2 } else { i + 1 };
+        let next_index = if skip_index == Some(i + 1) {
+            i + 2
+        } else {
+            i + 1
+        };
         if next_index >= values.len() {
             continue;
         }
@@ -41,13 +47,14 @@ fn is_safe_list(values: &Vec<i32>, is_increasing: bool, skip_index: Option<usize
 }
 
 fn is_safe_2(line: &str) -> bool {
-    let x = line.split_whitespace()
+    let x = line
+        .split_whitespace()
         .map(|n| n.parse::<i32>().unwrap())
         .collect_vec();
 
-    is_safe_list(&x, false, None) ||
-    is_safe_list(&x, true, None) ||
-    (0..x.len()).any(|i| is_safe_list(&x, true, Some(i)) || is_safe_list(&x, false, Some(i)))
+    is_safe_list(&x, false, None)
+        || is_safe_list(&x, true, None)
+        || (0..x.len()).any(|i| is_safe_list(&x, true, Some(i)) || is_safe_list(&x, false, Some(i)))
 }
 
 pub fn part_one(input: &str) -> Option<u32> {
@@ -58,9 +65,66 @@ pub fn part_two(input: &str) -> Option<u32> {
     Some(input.lines().map(is_safe_2).map(u32::from).sum())
 }
 
+/// Brute-force version of `is_safe_2`: tries removing every level.
+fn is_safe_2_brute_force(line: &str) -> bool {
+    let levels = line.split_whitespace().collect_vec();
+
+    (0..levels.len()).any(|i| {
+        let mut dampened = levels.clone();
+        dampened.remove(i);
+        is_safe_1(&dampened.join(" "))
+    })
+}
+
+pub fn part_two_brute_force(input: &str) -> Option<u32> {
+    Some(
+        input
+            .lines()
+            .map(is_safe_2_brute_force)
+            .map(u32::from)
+            .sum(),
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use advent_of_code::cross_check::{assert_cross_check, Rng};
+
+    /// Random reports with mostly small steps, so that safe and unsafe reports are both common.
+    fn generate_report(rng: &mut Rng) -> Vec<i32> {
+        let mut level = rng.range(1, 20) as i32;
+        (0..rng.range(2, 8))
+            .map(|_| {
+                level += rng.range(-4, 4) as i32;
+                level
+            })
+            .collect()
+    }
+
+    fn to_line(report: &[i32]) -> String {
+        report.iter().map(ToString::to_string).join(" ")
+    }
+
+    #[test]
+    fn cross_check_is_safe() {
+        assert_cross_check(
+            1000,
+            generate_report,
+            |report| is_safe_1(&to_line(report)),
+            |report| is_safe_list(report, true, None) || is_safe_list(report, false, None),
+        );
+    }
+
+    #[test]
+    fn cross_check_is_safe_with_dampener() {
+        assert_cross_check(
+            1000,
+            generate_report,
+            |report| is_safe_2(&to_line(report)),
+            |report| is_safe_2_brute_force(&to_line(report)),
+        );
+    }
 
     #[test]
     fn test_part_one() {
@@ -68,6 +132,12 @@ mod tests {
         assert_eq!(result, Some(2));
     }
 
+    #[test]
+    fn test_part_two_brute_force() {
+        let result = part_two_brute_force(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result, Some(4));
+    }
+
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
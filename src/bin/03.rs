@@ -1,10 +1,11 @@
+use advent_of_code::parse::{pair, unsigned};
 use nom::{
     branch::alt,
     bytes::complete::tag,
     character::complete::{anychar, char, digit1, none_of},
-    combinator::{map_res, recognize, verify},
-    multi::{many0, many1},
-    sequence::tuple,
+    combinator::{map_parser, verify},
+    multi::many0,
+    sequence::{delimited, tuple},
     IResult,
 };
 
@@ -21,19 +22,14 @@ fn noise(input: &str) -> IResult<&str, Vec<char>> {
 }
 
 fn number(input: &str) -> IResult<&str, u32> {
-    map_res(
-        verify(recognize(many1(digit1)), |s: &str| {
-            1 <= s.len() && s.len() <= 3
-        }),
-        |s: &str| s.parse::<u32>(),
-    )(input)
+    map_parser(verify(digit1, |s: &str| s.len() <= 3), unsigned)(input)
 }
 
 fn multiplication(input: &str) -> IResult<&str, Option<Multiplication>> {
     let (input, _) = tag("mul")(input)?;
 
-    match tuple((char('('), number, char(','), number, char(')')))(input) {
-        Ok((input, (_, x, _, y, _))) => Ok((input, Some(Multiplication { x, y }))),
+    match delimited(char('('), pair(",", number), char(')'))(input) {
+        Ok((input, (x, y))) => Ok((input, Some(Multiplication { x, y }))),
         Err(_) => Ok((input, None)),
     }
 }
@@ -1,171 +1,66 @@
-use itertools::Itertools;
+use advent_of_code::grid::{Grid, OFFSETS_8};
 
 advent_of_code::solution!(4);
 
 const SEARCH_WORD: &str = "XMAS";
 
-fn check_forward(grid: &Vec<Vec<char>>, row_idx: usize, col_idx: usize) -> u32 {
-    if grid[row_idx].len() - col_idx < SEARCH_WORD.len() {
-        // Not enough space to search
-        return 0;
-    }
-
-    for i in 0..SEARCH_WORD.len() {
-        if grid[row_idx][col_idx + i] != SEARCH_WORD.chars().nth(i).unwrap() {
-            return 0;
-        }
-    }
-    1
-}
-
-fn check_backward(grid: &Vec<Vec<char>>, row_idx: usize, col_idx: usize) -> u32 {
-    if col_idx < SEARCH_WORD.len() -1 {
-        // Not enough space to search
-        return 0;
-    }
-
-    for i in 0..SEARCH_WORD.len() {
-        if grid[row_idx][col_idx - i] != SEARCH_WORD.chars().nth(i).unwrap() {
-            return 0;
-        }
-    }
-    1
-}
-
-fn check_down(grid: &Vec<Vec<char>>, row_idx: usize, col_idx: usize) -> u32 {
-    if grid.len() - row_idx < SEARCH_WORD.len() {
-        // Not enough space to search
-        return 0;
-    }
-
-    for i in 0..SEARCH_WORD.len() {
-        if grid[row_idx + i][col_idx] != SEARCH_WORD.chars().nth(i).unwrap() {
-            return 0;
-        }
-    }
-    1
-}
-
-fn check_up(grid: &Vec<Vec<char>>, row_idx: usize, col_idx: usize) -> u32 {
-    if row_idx < SEARCH_WORD.len() - 1 {
-        // Not enough space to search
-        return 0;
-    }
-
-    for i in 0..SEARCH_WORD.len() {
-        if grid[row_idx - i][col_idx] != SEARCH_WORD.chars().nth(i).unwrap() {
-            return 0;
-        }
-    }
-    1
-}
-
-fn check_up_left(grid: &Vec<Vec<char>>, row_idx: usize, col_idx: usize) -> u32 {
-    if row_idx < SEARCH_WORD.len() - 1 || col_idx < SEARCH_WORD.len() - 1 {
-        // Not enough space to search
-        return 0;
-    }
+fn check_word(grid: &Grid<char>, position: (usize, usize), direction: (isize, isize)) -> u32 {
+    let mut current = Some(position);
 
-    for i in 0..SEARCH_WORD.len() {
-        if grid[row_idx - i][col_idx - i] != SEARCH_WORD.chars().nth(i).unwrap() {
-            return 0;
+    for c in SEARCH_WORD.chars() {
+        match current {
+            Some(pos) if grid[pos] == c => {
+                current = grid.offset(pos, direction);
+            }
+            // Not enough space to search or mismatch
+            _ => return 0,
         }
     }
     1
 }
 
-fn check_up_right(grid: &Vec<Vec<char>>, row_idx: usize, col_idx: usize) -> u32 {
-    if row_idx < SEARCH_WORD.len() - 1 || grid[row_idx].len() - col_idx < SEARCH_WORD.len() {
-        // Not enough space to search
-        return 0;
-    }
-
-    for i in 0..SEARCH_WORD.len() {
-        if grid[row_idx - i][col_idx + i] != SEARCH_WORD.chars().nth(i).unwrap() {
-            return 0;
-        }
-    }
-    1
-}
-
-fn check_down_left(grid: &Vec<Vec<char>>, row_idx: usize, col_idx: usize) -> u32 {
-    if grid.len() - row_idx < SEARCH_WORD.len() || col_idx < SEARCH_WORD.len() - 1 {
-        // Not enough space to search
-        return 0;
-    }
-
-    for i in 0..SEARCH_WORD.len() {
-        if grid[row_idx + i][col_idx - i] != SEARCH_WORD.chars().nth(i).unwrap() {
-            return 0;
-        }
-    }
-    1
-}
-
-fn check_down_right(grid: &Vec<Vec<char>>, row_idx: usize, col_idx: usize) -> u32 {
-    if grid.len() - row_idx < SEARCH_WORD.len() || grid[row_idx].len() - col_idx < SEARCH_WORD.len() {
-        // Not enough space to search
-        return 0;
-    }
-
-    for i in 0..SEARCH_WORD.len() {
-        if grid[row_idx + i][col_idx + i] != SEARCH_WORD.chars().nth(i).unwrap() {
-            return 0;
-        }
-    }
-    1
+fn parse_grid(input: &str) -> Option<Grid<char>> {
+    Grid::parse(input, |c| c).ok()
 }
 
 pub fn part_one(input: &str) -> Option<u32> {
-    let grid = input.lines().map(|line| line.chars().collect_vec()).collect_vec();
-    let mut xmas_count = 0;
-
-    for row_idx in 0..grid.len() {
-        for col_idx in 0..grid[row_idx].len() {
-            if grid[row_idx][col_idx] != 'X' {
-                continue;
-            }
+    let grid = parse_grid(input)?;
+
+    let xmas_count = grid
+        .iter()
+        .filter(|(_, c)| **c == 'X')
+        .map(|(pos, _)| {
+            OFFSETS_8
+                .iter()
+                .map(|direction| check_word(&grid, pos, *direction))
+                .sum::<u32>()
+        })
+        .sum();
 
-            xmas_count += check_forward(&grid, row_idx, col_idx);
-            xmas_count += check_backward(&grid, row_idx, col_idx);
-            xmas_count += check_down(&grid, row_idx, col_idx);
-            xmas_count += check_up(&grid, row_idx, col_idx);
-            xmas_count += check_up_left(&grid, row_idx, col_idx);
-            xmas_count += check_up_right(&grid, row_idx, col_idx);
-            xmas_count += check_down_left(&grid, row_idx, col_idx);
-            xmas_count += check_down_right(&grid, row_idx, col_idx);
-        }
-    }
     Some(xmas_count)
 }
 
-fn check_diagonals(grid: &Vec<Vec<char>>, row_idx: usize, col_idx: usize) -> u32 {
-    if row_idx < 1 || col_idx < 1 || grid.len() - row_idx <= 1 || grid[row_idx].len() - col_idx <= 1 {
-        // Not enough space to search
-        return 0;
-    }
-
-    let first = grid[row_idx - 1][col_idx - 1] == 'M' && grid[row_idx + 1][col_idx + 1] == 'S' ||
-    grid[row_idx - 1][col_idx - 1] == 'S' && grid[row_idx + 1][col_idx + 1] == 'M';
+fn check_diagonals(grid: &Grid<char>, position: (usize, usize)) -> u32 {
+    let corner = |offset| grid.offset(position, offset).map(|pos| grid[pos]);
 
-    let second = grid[row_idx - 1][col_idx + 1] == 'M' && grid[row_idx + 1][col_idx - 1] == 'S' ||
-    grid[row_idx - 1][col_idx + 1] == 'S' && grid[row_idx + 1][col_idx - 1] == 'M';
+    let is_mas = |a, b| {
+        matches!(
+            (corner(a), corner(b)),
+            (Some('M'), Some('S')) | (Some('S'), Some('M'))
+        )
+    };
 
-    (first && second) as u32
+    (is_mas((-1, -1), (1, 1)) && is_mas((1, -1), (-1, 1))) as u32
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
-    let grid = input.lines().map(|line| line.chars().collect_vec()).collect_vec();
-    let mut xmas_count = 0;
+    let grid = parse_grid(input)?;
 
-    for row_idx in 0..grid.len() {
-        for col_idx in 0..grid[row_idx].len() {
-            if grid[row_idx][col_idx] != 'A' {
-                continue;
-            }
-            xmas_count += check_diagonals(&grid, row_idx, col_idx);
-        }
-    }
+    let xmas_count = grid
+        .iter()
+        .filter(|(_, c)| **c == 'A')
+        .map(|(pos, _)| check_diagonals(&grid, pos))
+        .sum();
 
     Some(xmas_count)
 }
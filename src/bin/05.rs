@@ -1,128 +1,48 @@
-use std::collections::{HashMap, HashSet};
-
+use advent_of_code::order::Dag;
+use advent_of_code::parse::{list, parse_lines, sections, unsigned};
 use itertools::Itertools;
 
 advent_of_code::solution!(5);
 
-fn parse_dependencies(input: &str) -> HashMap<u32, Vec<u32>> {
-    let mut dependencies = HashMap::new();
-
-    for line in input.lines() {
-        let (dependency, dependent) = line.split_once('|').unwrap();
-
-        dependencies
-            .entry(dependent.parse().unwrap())
-            .or_insert_with(Vec::new)
-            .push(dependency.parse().unwrap());
-    }
-
-    dependencies
-}
-
-fn is_valid_vec(dependencies: &HashMap<u32, Vec<u32>>, updates: &Vec<u32>) -> bool {
-    let updates_set = updates.iter().copied().collect::<HashSet<_>>();
-    let mut updated = HashSet::new();
-
-    for update in updates {
-        if let Some(dependents) = dependencies.get(&update) {
-            for dependent in dependents {
-                if updates_set.contains(dependent) && !updated.contains(dependent) {
-                    return false;
-                }
-            }
-        }
-
-        updated.insert(update);
-    }
-
-    true
-}
-
-fn is_valid(dependencies: &HashMap<u32, Vec<u32>>, updates: &str) -> bool {
-    let updates = updates
-        .split(',')
-        .map(|update| update.parse().unwrap())
-        .collect_vec();
-
-    is_valid_vec(dependencies, &updates)
-}
-
-pub fn part_one(input: &str) -> Option<u32> {
-    let (dependencies, updates) = input.split_once("\n\n")?;
+fn parse(input: &str) -> Result<(Dag<u32>, Vec<Vec<u32>>), String> {
+    let (rules, updates) = sections(input)
+        .collect_tuple()
+        .ok_or("expected rules and updates separated by a blank line")?;
 
-    let dependencies = parse_dependencies(dependencies);
+    // line numbers in the errors count from the start of the section.
+    let rules = Dag::parse_rules(rules).map_err(|e| format!("rules: {e}"))?;
+    let updates = parse_lines(updates, list(",", unsigned)).map_err(|e| format!("updates: {e}"))?;
 
-    Some(
-        updates
-            .lines()
-            .filter(|line| is_valid(&dependencies, line))
-            .map(|line| {
-                line.split(',')
-                    .map(|update| update.parse::<u32>().unwrap())
-                    .collect_vec()
-            })
-            .map(|line| line[line.len() / 2])
-            .sum(),
-    )
+    Ok((rules, updates))
 }
 
-fn return_invalid(dependencies: &HashMap<u32, Vec<u32>>, updates: &str) -> Option<Vec<u32>> {
-    let updates = updates
-        .split(',')
-        .map(|update| update.parse().unwrap())
-        .collect_vec();
-
-    if is_valid_vec(dependencies, &updates) {
-        return None;
-    }
-
-    Some(updates)
+fn middle(update: &[u32]) -> u32 {
+    update[update.len() / 2]
 }
 
-fn fix_invalid(dependencies: &HashMap<u32, Vec<u32>>, invalid: &Vec<u32>) -> Vec<u32> {
-    let mut invalid = invalid.clone();
+pub fn part_one(input: &str) -> Result<u32, String> {
+    let (rules, updates) = parse(input)?;
 
-    let mut i = 0;
-
-    while i < invalid.len() {
-        let update = invalid[i];
-        let mut should_increment = true;
-
-        if let Some(dependents) = dependencies.get(&update) {
-            for dependent in dependents {
-                if let Some(index) = invalid.iter().position(|&x| x == *dependent) {
-                    if index < i {
-                        continue;
-                    }
-                    should_increment = false;
-                    invalid.remove(index);
-                    invalid.insert(i, *dependent);
-                }
-            }
-        }
-
-        if should_increment {
-            i += 1;
-        }
-    }
-
-    invalid
+    Ok(updates
+        .iter()
+        .filter(|update| rules.is_ordered(update))
+        .map(|update| middle(update))
+        .sum())
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    let (dependencies, updates) = input.split_once("\n\n")?;
-
-    let dependencies = parse_dependencies(dependencies);
-
-    Some(
-        updates
-            .lines()
-            .map(|line| return_invalid(&dependencies, line))
-            .flatten()
-            .map(|invalid| fix_invalid(&dependencies, &invalid))
-            .map(|line| line[line.len() / 2])
-            .sum(),
-    )
+pub fn part_two(input: &str) -> Result<u32, String> {
+    let (rules, updates) = parse(input)?;
+
+    updates
+        .into_iter()
+        .filter(|update| !rules.is_ordered(update))
+        .map(|mut update| {
+            // the rules are only acyclic within an update, so each update gets its own order.
+            let order = rules.order(&update).map_err(|e| e.to_string())?;
+            order.sort(&mut update);
+            Ok(middle(&update))
+        })
+        .sum()
 }
 
 #[cfg(test)]
@@ -132,12 +52,22 @@ mod tests {
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(143));
+        assert_eq!(result, Ok(143));
     }
 
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(123));
+        assert_eq!(result, Ok(123));
+    }
+
+    #[test]
+    fn test_malformed_input() {
+        let input = "47|53\n97|13\n\n75,47,61\nx,53\n";
+        let error = "updates: line 2, column 1: expected a number near \"x,53\"";
+        assert_eq!(part_one(input), Err(error.to_string()));
+        assert_eq!(part_two(input), Err(error.to_string()));
+
+        assert!(part_one("47|53\n").is_err());
     }
 }
@@ -1,79 +1,34 @@
-use std::{collections::HashSet, hash::Hash};
+use std::{hash::Hash, ops::Add};
 
-use itertools::Itertools;
+use advent_of_code::{bitset::BitGrid, direction::Direction, grid::Grid, parallel};
 
 advent_of_code::solution!(6);
 
-#[derive(Debug, PartialEq, Clone, Copy, Hash, Eq)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right
-}
-
 #[derive(Debug, PartialEq, Clone, Copy, Hash, Eq)]
 enum Square {
     Empty,
     Obstacle,
-    Guard(Direction)
+    Guard(Direction),
 }
 
 impl Square {
-
     fn from_char(s: char) -> Result<Self, ()> {
         match s {
             '.' => Ok(Square::Empty),
             '#' => Ok(Square::Obstacle),
-            'v' => Ok(Square::Guard(Direction::Down)),
-            '^' => Ok(Square::Guard(Direction::Up)),
-            '<' => Ok(Square::Guard(Direction::Left)),
-            '>' => Ok(Square::Guard(Direction::Right)),
-            _ => Err(())
+            _ => Direction::try_from(s).map(Square::Guard).map_err(|_| ()),
         }
     }
 }
 
-fn get_new_position(map: &Vec<Vec<Square>>, row: usize, col: usize, direction: Direction) -> Option<(usize, usize)> {
-    match direction {
-        Direction::Up => {
-            if row == 0 {
-                None
-            } else {
-                Some((row - 1, col))
-            }
-        },
-        Direction::Down => {
-            if row == map.len() - 1 {
-                None
-            } else {
-                Some((row + 1, col))
-            }
-        },
-        Direction::Left => {
-            if col == 0 {
-                None
-            } else {
-                Some((row, col - 1))
-            }
-        },
-        Direction::Right => {
-            if col == map[0].len() - 1 {
-                None
-            } else {
-                Some((row, col + 1))
-            }
-        }
-    }
-}
-
-fn rotate_right(direction: Direction) -> Direction {
-    match direction {
-        Direction::Up => Direction::Right,
-        Direction::Right => Direction::Down,
-        Direction::Down => Direction::Left,
-        Direction::Left => Direction::Up
-    }
+fn get_new_position(
+    map: &Grid<Square>,
+    row: usize,
+    col: usize,
+    direction: Direction,
+) -> Option<(usize, usize)> {
+    map.offset((col, row), direction.delta())
+        .map(|(col, row)| (row, col))
 }
 
 pub fn part_one(input: &str) -> Option<u32> {
@@ -88,106 +43,88 @@ type Guard = (usize, usize, Direction);
 
 enum Step {
     Step(Guard),
-    OutOfBounds
+    OutOfBounds,
 }
 
 struct Map {
-    map: Vec<Vec<Square>>,
+    map: Grid<Square>,
 }
 
 impl Map {
-    fn step(&self, (row, col, direction): Guard) -> Step {
+    /// Moves the guard one step. `obstacle` is an additional obstacle at `(col, row)`
+    fn step(&self, (row, col, direction): Guard, obstacle: Option<(usize, usize)>) -> Step {
         if let Some((new_row, new_col)) = get_new_position(&self.map, row, col, direction) {
-            if self.map[new_row][new_col] != Square::Obstacle {
-                return Step::Step((new_row, new_col, direction));
+            if self.map[(new_col, new_row)] != Square::Obstacle
+                && obstacle != Some((new_col, new_row))
+            {
+                Step::Step((new_row, new_col, direction))
             } else {
-                return Step::Step((row, col, rotate_right(direction)));
+                Step::Step((row, col, direction.rotate_right()))
             }
         } else {
-            return Step::OutOfBounds;
+            Step::OutOfBounds
         }
     }
 
     fn new(input: &str) -> Map {
         Map {
-            map: input.lines().map(|row| row.chars().map(|c| Square::from_char(c).unwrap()).collect_vec()).collect_vec()
+            map: Grid::try_parse(input, |c| Square::from_char(c).ok()).unwrap(),
         }
     }
 
     fn initial_guard(&self) -> Guard {
-        let (guard_row, guard_col) = self.map.iter().enumerate().find_map(|(row_idx, row)| {
-            row.iter().enumerate().find_map(|(col_idx, square)| {
-                match square {
-                    Square::Guard(_) => Some((row_idx, col_idx)),
-                    _ => None
-                }
-            })
-        }).unwrap();
-
-        match self.map[guard_row][guard_col] {
+        let (guard_col, guard_row) = self
+            .map
+            .find(|square| matches!(square, Square::Guard(_)))
+            .unwrap();
+
+        match self.map[(guard_col, guard_row)] {
             Square::Guard(direction) => (guard_row, guard_col, direction),
-            _ => panic!("Guard not found")
+            _ => panic!("Guard not found"),
         }
     }
 
-    fn visited_locations(&self, mut guard: Guard) -> HashSet<(usize, usize)> {
-        let mut visited = HashSet::new();
-        visited.insert((guard.0, guard.1));
-
-        loop {
-            match self.step(guard) {
-                Step::Step((new_row, new_col, new_direction)) => {
-                    visited.insert((new_row, new_col));
-                    guard = (new_row, new_col, new_direction);
-                },
-                Step::OutOfBounds => {
-                    break;
-                }
-            }
+    fn visited_locations(&self, mut guard: Guard) -> BitGrid {
+        let mut visited = BitGrid::new(self.map.width(), self.map.height());
+        visited.insert((guard.1, guard.0));
+
+        while let Step::Step((new_row, new_col, new_direction)) = self.step(guard, None) {
+            visited.insert((new_col, new_row));
+            guard = (new_row, new_col, new_direction);
         }
 
         visited
     }
 
-    fn possible_loops(&mut self, guard: Guard) -> usize {
-        let mut loops = 0;
-
-        let visited_locations = self.visited_locations(guard);
-
-        for (row, col) in visited_locations {
-            let initial = self.map[row][col];
-            self.map[row][col] = Square::Obstacle;
-
-            if self.is_loop(guard) {
-                loops += 1;
-            }
-
-            self.map[row][col] = initial;
-        }
-
-        loops
+    fn possible_loops(&self, guard: Guard) -> usize {
+        let visited_locations = self
+            .visited_locations(guard)
+            .positions()
+            .collect::<Vec<_>>();
+
+        // every thread reuses one set of seen states, clearing it is O(1)
+        parallel::map_reduce_with(
+            &visited_locations,
+            || BitGrid::with_layers(self.map.width(), self.map.height(), Direction::ALL.len()),
+            |seen, &obstacle| {
+                seen.clear();
+                usize::from(self.is_loop(guard, obstacle, seen))
+            },
+            Add::add,
+        )
+        .unwrap_or_default()
     }
 
-    fn is_loop(&self, (row, col, direction): Guard) -> bool {
-        let mut current = (row, col, direction);
-
-        let mut visited = HashSet::new();
-        visited.insert((row, col, direction));
+    fn is_loop(&self, mut guard: Guard, obstacle: (usize, usize), seen: &mut BitGrid) -> bool {
+        seen.insert_in_layer((guard.1, guard.0), guard.2 as usize);
 
         // Step until we reach the same position and direction again or we go out of bounds
-        loop {
-            match self.step(current) {
-                Step::Step((new_row, new_col, new_direction)) => {
-                    if visited.contains(&(new_row, new_col, new_direction)) {
-                        return true;
-                    }
-                    visited.insert((new_row, new_col, new_direction));
-                    current = (new_row, new_col, new_direction);
-                },
-                Step::OutOfBounds => {
-                    break;
-                }
+        while let Step::Step(next) = self.step(guard, Some(obstacle)) {
+            let (row, col, direction) = next;
+            if !seen.insert_in_layer((col, row), direction as usize) {
+                return true;
             }
+            guard = next;
         }
 
         false
@@ -195,7 +132,7 @@ impl Map {
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
-    let mut map = Map::new(input);
+    let map = Map::new(input);
 
     let guard = map.initial_guard();
 
@@ -1,5 +1,10 @@
+use std::ops::Add;
+
+use advent_of_code::math::Digits;
+use advent_of_code::parallel;
+use advent_of_code::parse::{list, parse_lines, unsigned, ParseError};
 use itertools::Itertools;
-use nom::{bytes::complete::tag, character::complete::{char, digit1}, multi::separated_list1, IResult};
+use nom::{bytes::complete::tag, IResult};
 
 advent_of_code::solution!(7);
 
@@ -13,7 +18,7 @@ struct Equation {
 enum Operator {
     Add,
     Multiply,
-    Concat
+    Concat,
 }
 
 impl Equation {
@@ -28,7 +33,11 @@ impl Equation {
                 Operator::Add => evaluated_total += num,
                 Operator::Multiply => evaluated_total *= num,
                 Operator::Concat => {
-                    evaluated_total = evaluated_total * 10u64.pow(num.ilog10() + 1) + num;
+                    // the total only grows, so an overflow can never match.
+                    let Some(total) = evaluated_total.concat(num) else {
+                        return false;
+                    };
+                    evaluated_total = total;
                 }
             }
         }
@@ -38,47 +47,42 @@ impl Equation {
 }
 
 fn parse_line(input: &str) -> IResult<&str, Equation> {
-    let (input, total) =digit1(input)?;
+    let (input, total) = unsigned(input)?;
     let (input, _) = tag(": ")(input)?;
+    let (input, nums) = list(" ", unsigned)(input)?;
 
-    let (input, nums) = separated_list1(char(' '), digit1)(input)?;
-    let (input, _) = tag("\n")(input)?;
-
-    Ok((input, Equation { total: total.parse().unwrap(), nums: nums.iter().map(|n| n.parse().unwrap()).collect() }))
+    Ok((input, Equation { total, nums }))
 }
 
-fn parse(mut input: &str) -> Vec<Equation> {
-    let mut equations = vec![];
-
-    while let Ok((new_input, eq)) = parse_line(input) {
-        equations.push(eq);
-        input = new_input;
-    }
-
-    equations
+fn parse(input: &str) -> Result<Vec<Equation>, ParseError> {
+    parse_lines(input, parse_line)
 }
 
-
-fn solve(input: &str, operators: &[Operator]) -> Option<u64> {
-    let equations = parse(input);
-
-    equations.iter().map(|eq| {
-        (0..(eq.nums.len() - 1)).map(|_| operators.iter()).multi_cartesian_product().map(|operators| {
-            if eq.is_valid(&operators) {
-                return Some(eq.total)
-            }
-            None
-        }).filter(Option::is_some).next()
-    }).filter(Option::is_some).map(Option::unwrap).sum()
+fn solve(input: &str, operators: &[Operator]) -> Result<u64, ParseError> {
+    let equations = parse(input)?;
+
+    let calibration = parallel::map_reduce(
+        &equations,
+        |eq| {
+            (0..(eq.nums.len() - 1))
+                .map(|_| operators.iter())
+                .multi_cartesian_product()
+                .find(|operators| eq.is_valid(operators))
+                .map_or(0, |_| eq.total)
+        },
+        Add::add,
+    );
+
+    Ok(calibration.unwrap_or_default())
 }
 
 const OPERATORS_P1: &[Operator] = &[Operator::Add, Operator::Multiply];
-pub fn part_one(input: &str) -> Option<u64> {
+pub fn part_one(input: &str) -> Result<u64, ParseError> {
     solve(input, OPERATORS_P1)
 }
 
 const OPERATORS_P2: &[Operator] = &[Operator::Add, Operator::Multiply, Operator::Concat];
-pub fn part_two(input: &str) -> Option<u64> {
+pub fn part_two(input: &str) -> Result<u64, ParseError> {
     solve(input, OPERATORS_P2)
 }
 
@@ -89,12 +93,12 @@ mod tests {
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(3749));
+        assert_eq!(result, Ok(3749));
     }
 
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(11387));
+        assert_eq!(result, Ok(11387));
     }
 }
@@ -1,5 +1,6 @@
 use std::{collections::HashMap, str::FromStr};
 
+use advent_of_code::{grid::Grid, parse::ParseError, point::Point};
 use itertools::Itertools;
 
 advent_of_code::solution!(8);
@@ -10,62 +11,46 @@ enum Square {
 }
 
 struct Map {
-    width: usize,
-    height: usize,
-    grid: Vec<Vec<Square>>,
+    grid: Grid<Square>,
 }
 
 impl FromStr for Map {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let grid: Vec<Vec<Square>> = s
-            .lines()
-            .map(|line| {
-                line.chars()
-                    .map(|c| match c {
-                        '.' => Square::Empty,
-                        _ => Square::Antenna(c),
-                    })
-                    .collect()
-            })
-            .collect();
+        let grid = Grid::parse(s, |c| match c {
+            '.' => Square::Empty,
+            _ => Square::Antenna(c),
+        })?;
 
-        Ok(Map {
-            width: grid[0].len(),
-            height: grid.len(),
-            grid,
-        })
+        Ok(Map { grid })
     }
 }
 
 impl Map {
-    fn get_antennas(&self) -> HashMap<char, Vec<(i32, i32)>> {
+    fn get_antennas(&self) -> HashMap<char, Vec<Point>> {
         let mut antennas = HashMap::new();
 
-        for y in 0..self.height {
-            for x in 0..self.width {
-                if let Square::Antenna(c) = self.grid[y][x] {
-                    antennas
-                        .entry(c)
-                        .or_insert(Vec::new())
-                        .push((x as i32, y as i32));
-                }
+        for ((x, y), square) in self.grid.iter() {
+            if let Square::Antenna(c) = square {
+                antennas
+                    .entry(*c)
+                    .or_insert(Vec::new())
+                    .push(Point::from((x, y)));
             }
         }
 
         antennas
     }
 
-    fn within_bounds(&self, x: i32, y: i32) -> bool {
-        0 <= x && x < self.width as i32 && 0 <= y && y < self.height as i32
+    fn within_bounds(&self, point: Point) -> bool {
+        self.grid.get_point(point).is_some()
     }
 }
 
-fn solve(
-    input: &str,
-    nodes: fn(&Map, ((i32, i32), (i32, i32))) -> Option<Vec<(i32, i32)>>,
-) -> Option<u32> {
+type NodeFn = fn(&Map, (Point, Point)) -> Option<Vec<Point>>;
+
+fn solve(input: &str, nodes: NodeFn) -> Option<u32> {
     let map: Map = input.parse().ok()?;
 
     let antennas = map.get_antennas();
@@ -77,11 +62,9 @@ fn solve(
                 antennas[key]
                     .iter()
                     .cartesian_product(antennas[key].iter())
-                    .map(|(a, b)| nodes(&map, (*a, *b)))
-                    .filter(Option::is_some)
-                    .map(Option::unwrap)
+                    .filter_map(|(a, b)| nodes(&map, (*a, *b)))
                     .flatten()
-                    .filter(|&(x, y)| map.within_bounds(x, y))
+                    .filter(|&point| map.within_bounds(point))
             })
             .unique()
             .count() as u32,
@@ -93,12 +76,9 @@ pub fn part_one(input: &str) -> Option<u32> {
         if a == b {
             return None;
         }
-        let diff = (b.0 - a.0, b.1 - a.1);
+        let diff = b - a;
 
-        Some(vec![
-            (a.0 - diff.0, a.1 - diff.1),
-            (b.0 + diff.0, b.1 + diff.1),
-        ])
+        Some(vec![a - diff, b + diff])
     })
 }
 
@@ -107,18 +87,18 @@ pub fn part_two(input: &str) -> Option<u32> {
         if a == b {
             return None;
         }
-        let diff = (b.0 - a.0, b.1 - a.1);
+        let diff = b - a;
         let mut anti_nodes = vec![a, b];
 
         let mut last = a;
-        while map.within_bounds(last.0, last.1) {
-            last = (last.0 - diff.0, last.1 - diff.1);
+        while map.within_bounds(last) {
+            last -= diff;
             anti_nodes.push(last);
         }
 
         last = b;
-        while map.within_bounds(last.0, last.1) {
-            last = (last.0 + diff.0, last.1 + diff.1);
+        while map.within_bounds(last) {
+            last += diff;
             anti_nodes.push(last);
         }
 
@@ -6,12 +6,11 @@ pub fn part_one(input: &str) -> Option<u64> {
     let mut x = input
         .chars()
         .enumerate()
-        .map(|(i, c)| {
+        .filter_map(|(i, c)| {
             c.to_digit(10)
                 .map(|d| vec![if i % 2 == 0 { Some(i as u64 / 2) } else { None }; d as usize])
         })
         .flatten()
-        .flatten()
         .collect_vec();
 
     let mut right = 0;
@@ -44,15 +43,13 @@ pub fn part_two(input: &str) -> Option<u64> {
     let mut x = input
         .chars()
         .enumerate()
-        .map(|(i, c)| {
+        .filter_map(|(i, c)| {
             c.to_digit(10).map(|d| Block {
                 value: if i % 2 == 0 { Some(i as u64 / 2) } else { None },
                 size: d as usize,
                 has_tried_to_move: false,
             })
         })
-        .filter(Option::is_some)
-        .map(Option::unwrap)
         .collect_vec();
 
     while let Some(last_block_idx) = x
@@ -93,19 +90,17 @@ pub fn part_two(input: &str) -> Option<u64> {
     let mut idx = 0;
     let mut sum = 0u64;
 
-    for i in 0..x.len() {
-        for _ in 0..x[i].size {
-            if x[i].value.is_some() {
-                sum += x[i].value.unwrap() * idx as u64;
+    for block in &x {
+        for _ in 0..block.size {
+            if let Some(value) = block.value {
+                sum += value * idx as u64;
             }
 
             idx += 1;
         }
     }
 
-    Some(
-        sum
-    )
+    Some(sum)
 }
 
 #[cfg(test)]
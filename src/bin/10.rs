@@ -1,67 +1,40 @@
 use std::str::FromStr;
 
+use advent_of_code::{bitset::BitGrid, grid::Grid, parse::ParseError};
+
 advent_of_code::solution!(10);
 
 struct Map {
-    map: Vec<Vec<u32>>,
+    map: Grid<u32>,
 }
 
 impl FromStr for Map {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
         Ok(Map {
-            map: input
-                .lines()
-                .map(|line| line.chars().map(|c| c.to_digit(10).unwrap()).collect())
-                .collect(),
+            map: Grid::try_parse(input, |c| c.to_digit(10))?,
         })
     }
 }
 
 impl Map {
     fn get_starting_points(&self) -> Vec<(usize, usize)> {
-        let mut starts = Vec::new();
-
-        for (y, row) in self.map.iter().enumerate() {
-            for (x, &cell) in row.iter().enumerate() {
-                if cell == 0 {
-                    starts.push((x, y));
-                }
-            }
-        }
-
-        starts
-    }
-
-    fn get_neighbors(&self, (x, y): (usize, usize)) -> Vec<(usize, usize)> {
-        let mut neighbors = Vec::new();
-
-        if x > 0 {
-            neighbors.push((x - 1, y));
-        }
-        if x < self.map[0].len() - 1 {
-            neighbors.push((x + 1, y));
-        }
-        if y > 0 {
-            neighbors.push((x, y - 1));
-        }
-        if y < self.map.len() - 1 {
-            neighbors.push((x, y + 1));
-        }
-
-        neighbors
+        self.map
+            .iter()
+            .filter(|(_, &cell)| cell == 0)
+            .map(|(pos, _)| pos)
+            .collect()
     }
 
-    fn valid_neighbors(&self, (x, y): (usize, usize)) -> Vec<(usize, usize)> {
-        self.get_neighbors((x, y))
-            .into_iter()
-            .filter(|pos| self.get_cell(*pos) == self.get_cell((x, y)) + 1)
-            .collect()
+    fn valid_neighbors(&self, pos: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.map
+            .neighbours_4(pos)
+            .filter(move |neighbor| self.get_cell(*neighbor) == self.get_cell(pos) + 1)
     }
 
-    fn get_cell(&self, (x, y): (usize, usize)) -> u32 {
-        self.map[y][x]
+    fn get_cell(&self, pos: (usize, usize)) -> u32 {
+        self.map[pos]
     }
 }
 
@@ -71,17 +44,16 @@ pub fn part_one(input: &str) -> Option<u32> {
     let starting_points = map.get_starting_points();
 
     let mut amount_of_reachable_goals = 0;
+    let mut visited = BitGrid::new(map.map.width(), map.map.height());
     for starting_point in starting_points {
-        let mut visited = vec![vec![false; map.map[0].len()]; map.map.len()];
+        visited.clear();
         let mut queue = vec![starting_point];
 
         while let Some((x, y)) = queue.pop() {
-            if visited[y][x] {
+            if !visited.insert((x, y)) {
                 continue;
             }
 
-            visited[y][x] = true;
-
             if map.get_cell((x, y)) == 9 {
                 amount_of_reachable_goals += 1;
             }
@@ -102,12 +74,10 @@ pub fn part_two(input: &str) -> Option<u32> {
 
     let mut amount_of_reachable_goals = 0;
     for starting_point in starting_points {
-        let mut visited = vec![vec![false; map.map[0].len()]; map.map.len()];
+        // every path to a summit counts, so positions may be visited more than once.
         let mut queue = vec![starting_point];
 
         while let Some((x, y)) = queue.pop() {
-            visited[y][x] = true;
-
             if map.get_cell((x, y)) == 9 {
                 amount_of_reachable_goals += 1;
             }
@@ -1,50 +1,40 @@
-use std::{collections::HashMap, mem::swap};
+use advent_of_code::counter::Counter;
+use advent_of_code::math::Digits;
 
 advent_of_code::solution!(11);
 
 fn solve(input: &str, steps: usize) -> Option<u64> {
-    let mut stone_count_1 = input
+    let mut stones = input
         .split_whitespace()
-        .map(|s| (s.parse::<u64>().unwrap(), 1))
-        .collect::<HashMap<u64, u64>>();
-    let mut stone_count_2 = HashMap::<u64, u64>::new();
+        .map(|s| s.parse::<u64>().ok())
+        .collect::<Option<Counter<u64>>>()?;
 
     for _ in 0..steps {
-        for (stone, count) in stone_count_1.iter() {
-            match stone {
-                0 => {
-                    *stone_count_2.entry(1).or_insert(0) += count;
-                }
-                n if i64::ilog10(*n as i64) % 2 == 1 => {
-                    let len = i64::ilog10(*stone as i64) + 1;
-
-                    let pow = 10u64.pow(len / 2 as u32);
-
-                    let first = n / pow;
-                    let second = n % pow;
-
-                    *stone_count_2.entry(first).or_insert(0) += count;
-                    *stone_count_2.entry(second).or_insert(0) += count;
-                }
-                n => {
-                    *stone_count_2.entry(n * 2024).or_insert(0) += count;
-                }
+        stones.transform(|&stone, count, next| match stone {
+            0 => {
+                next.add(1, count);
             }
-        }
+            n if n.digit_count() % 2 == 0 => {
+                let (first, second) = n.split_digits(n.digit_count() / 2);
 
-        swap(&mut stone_count_1, &mut stone_count_2);
-        stone_count_2.clear();
+                next.add(first, count);
+                next.add(second, count);
+            }
+            n => {
+                next.add(n * 2024, count);
+            }
+        });
     }
 
-    Some(stone_count_1.values().sum::<u64>())
+    Some(stones.total())
 }
 
 pub fn part_one(input: &str) -> Option<u64> {
-    Some(solve(input, 25)?)
+    solve(input, 25)
 }
 
 pub fn part_two(input: &str) -> Option<u64> {
-    Some(solve(input, 75)?)
+    solve(input, 75)
 }
 
 #[cfg(test)]
@@ -1,53 +1,32 @@
-use nom::{
-    branch::alt, bytes::complete::tag, character::complete::digit1, combinator::opt, IResult,
-};
+use advent_of_code::linear::System;
+use advent_of_code::parse::{labelled, parse_all, unsigned, ParseError};
+use advent_of_code::point::{Point, Vector};
+use nom::{branch::alt, bytes::complete::tag, combinator::opt, multi::many1, IResult};
 
 advent_of_code::solution!(13);
 
-#[derive(Debug, Clone, Copy)]
-struct Vector {
-    x: i64,
-    y: i64,
-}
-
 struct Game {
     a_button: Vector,
     b_button: Vector,
-    prize: Vector,
+    prize: Point,
 }
 
-fn parse_price(input: &str) -> IResult<&str, Vector> {
-    let (input, _) = tag("Prize: X=")(input)?;
-    let (input, x) = digit1(input)?;
-    let (input, _) = tag(", Y=")(input)?;
-    let (input, y) = digit1(input)?;
+fn parse_price(input: &str) -> IResult<&str, Point> {
+    let (input, x) = labelled("Prize: X=", unsigned)(input)?;
+    let (input, y) = labelled(", Y=", unsigned)(input)?;
     let (input, _) = tag("\n")(input)?;
 
-    Ok((
-        input,
-        Vector {
-            x: x.parse().unwrap(),
-            y: y.parse().unwrap(),
-        },
-    ))
+    Ok((input, Point::new(x, y)))
 }
 
 fn parse_vector(input: &str) -> IResult<&str, Vector> {
     let (input, _) = tag("Button ")(input)?;
     let (input, _) = alt((tag("A: "), tag("B: ")))(input)?;
-    let (input, _) = tag("X+")(input)?;
-    let (input, x) = digit1(input)?;
-    let (input, _) = tag(", Y+")(input)?;
-    let (input, y) = digit1(input)?;
+    let (input, x) = labelled("X+", unsigned)(input)?;
+    let (input, y) = labelled(", Y+", unsigned)(input)?;
     let (input, _) = tag("\n")(input)?;
 
-    Ok((
-        input,
-        Vector {
-            x: x.parse().unwrap(),
-            y: y.parse().unwrap(),
-        },
-    ))
+    Ok((input, Vector::new(x, y)))
 }
 
 fn parse_game(input: &str) -> IResult<&str, Game> {
@@ -66,10 +45,8 @@ fn parse_game(input: &str) -> IResult<&str, Game> {
     ))
 }
 
-fn parse_games(input: &str) -> IResult<&str, Vec<Game>> {
-    let (input, games) = nom::multi::many0(parse_game)(input)?;
-
-    Ok((input, games))
+fn parse_games(input: &str) -> Result<Vec<Game>, ParseError> {
+    parse_all(input, many1(parse_game))
 }
 
 fn solve_euler(game: &Game) -> Option<u64> {
@@ -77,54 +54,36 @@ fn solve_euler(game: &Game) -> Option<u64> {
     let b = &game.b_button;
     let p = &game.prize;
 
-    let det = a.x * b.y - a.y * b.x;
-    let x = (b.y * p.x - b.x * p.y) / det;
-    let y = (a.x * p.y - a.y * p.x) / det;
+    let system = System::new(
+        vec![vec![a.x.into(), b.x.into()], vec![a.y.into(), b.y.into()]],
+        vec![p.x.into(), p.y.into()],
+    );
 
-    // The solution we found might be floored due to integer division, so we need to check if it's valid
-    if x < 0 || y < 0 || a.x * x + b.x * y != p.x || a.y * x + b.y * y != p.y {
-        return None;
-    }
-
-    let result = x * 3 + y;
+    // Button presses cost 3 and 1 tokens. If both buttons move the claw in the same direction,
+    // no button can be pressed more often than the prize is away.
+    let (tokens, _) = system.min_cost_non_negative(&[3, 1], p.x.max(p.y).into())?;
 
-    Some(result as u64)
+    u64::try_from(tokens).ok()
 }
 
-pub fn part_one(input: &str) -> Option<u64> {
-    let games = parse_games(input).ok()?.1;
-
-    Some(
-        games
-            .iter()
-            .map(solve_euler)
-            .filter(Option::is_some)
-            .map(Option::unwrap)
-            .sum(),
-    )
+pub fn part_one(input: &str) -> Result<u64, ParseError> {
+    let games = parse_games(input)?;
+
+    Ok(games.iter().filter_map(solve_euler).sum())
 }
 
-pub fn part_two(input: &str) -> Option<u64> {
-    let games = parse_games(input).ok()?.1;
-
-    Some(
-        games
-            .iter()
-            .map(|game| {
-                return Game {
-                    a_button: game.a_button,
-                    b_button: game.b_button,
-                    prize: Vector {
-                        x: game.prize.x + 10_000_000_000_000,
-                        y: game.prize.y + 10_000_000_000_000,
-                    },
-                };
-            })
-            .map(|game| solve_euler(&game))
-            .filter(Option::is_some)
-            .map(Option::unwrap)
-            .sum(),
-    )
+pub fn part_two(input: &str) -> Result<u64, ParseError> {
+    let games = parse_games(input)?;
+
+    Ok(games
+        .iter()
+        .map(|game| Game {
+            a_button: game.a_button,
+            b_button: game.b_button,
+            prize: game.prize + Vector::new(10_000_000_000_000, 10_000_000_000_000),
+        })
+        .filter_map(|game| solve_euler(&game))
+        .sum())
 }
 
 #[cfg(test)]
@@ -134,12 +93,12 @@ mod tests {
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(480));
+        assert_eq!(result, Ok(480));
     }
 
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(875318608908));
+        assert_eq!(result, Ok(875318608908));
     }
 }
@@ -1,58 +1,67 @@
-use std::{collections::HashSet, fmt::Display};
-
-use nom::{
-    bytes::complete::tag,
-    character::complete::digit1,
-    combinator::{opt, recognize},
-    sequence::preceded,
-    IResult,
-};
+use std::fmt::Display;
+
+use advent_of_code::cycle::brent;
+use advent_of_code::direction::Direction8;
+use advent_of_code::grid::Grid;
+use advent_of_code::hash::{FastSet, HashSetExt};
+use advent_of_code::image::{Image, Rgb};
+use advent_of_code::math::lcm;
+use advent_of_code::parse::{coordinates, labelled, parse_lines, ParseError};
+use advent_of_code::point::{Point, Vector};
+use nom::IResult;
 
 advent_of_code::solution!(14);
 
 struct Robot {
-    x: i32,
-    y: i32,
-    v_x: i32,
-    v_y: i32,
+    position: Point,
+    velocity: Vector,
 }
 
 struct Map {
     robots: Vec<Robot>,
-    size: (i32, i32),
+    size: Vector,
 }
 
 impl Map {
     fn step(&mut self) {
         for robot in &mut self.robots {
-            robot.x += robot.v_x;
-            robot.y += robot.v_y;
-
             // the robot positions will wrap around the map
-            // make sure they are always positive
-            robot.x = ((robot.x % self.size.0) + self.size.0) % self.size.0;
-            robot.y = ((robot.y % self.size.1) + self.size.1) % self.size.1;
+            robot.position = (robot.position + robot.velocity).rem_euclid(self.size);
         }
     }
 
+    /// Number of steps after which all robots are back at their positions: the lcm of the cycle
+    /// lengths of the single robots, which is at most width * height.
+    fn period(&self) -> u64 {
+        self.robots
+            .iter()
+            .map(|robot| {
+                let cycle = brent(robot.position, |&position| {
+                    Some((position + robot.velocity).rem_euclid(self.size))
+                });
+                cycle.expect("robots move forever").length as u64
+            })
+            .fold(1, lcm)
+    }
+
     fn safety_factor(&self) -> u32 {
         // Get robots in each quadrant, robots between quadrants are ignored
-        let mut quadrants = vec![0, 0, 0, 0];
-        let half_size = (self.size.0 / 2, self.size.1 / 2);
+        let mut quadrants = [0, 0, 0, 0];
+        let half_size = Point::new(self.size.x / 2, self.size.y / 2);
 
-        for robot in &self.robots {
-            if robot.x == half_size.0 || robot.y == half_size.1 {
+        for Robot { position, .. } in &self.robots {
+            if position.x == half_size.x || position.y == half_size.y {
                 // The robot is on the border of the map, ignore it
                 continue;
             }
-            let quadrant = if robot.x < half_size.0 {
-                if robot.y < half_size.1 {
+            let quadrant = if position.x < half_size.x {
+                if position.y < half_size.y {
                     0
                 } else {
                     1
                 }
             } else {
-                if robot.y < half_size.1 {
+                if position.y < half_size.y {
                     2
                 } else {
                     3
@@ -65,101 +74,121 @@ impl Map {
     }
 
     fn average_robot_density(&self) -> u32 {
-        let mut pos_set = HashSet::<(i32, i32)>::new();
+        let mut pos_set = FastSet::<Point>::with_capacity(self.robots.len());
         for robot in &self.robots {
-            pos_set.insert((robot.x, robot.y));
+            pos_set.insert(robot.position);
         }
 
         self.robots
             .iter()
             .map(|robot| {
-                pos_set.contains(&(robot.x - 1, robot.y - 1)) as u32
-                    + pos_set.contains(&(robot.x, robot.y - 1)) as u32
-                    + pos_set.contains(&(robot.x + 1, robot.y - 1)) as u32
-                    + pos_set.contains(&(robot.x - 1, robot.y)) as u32
-                    + pos_set.contains(&(robot.x + 1, robot.y)) as u32
-                    + pos_set.contains(&(robot.x - 1, robot.y + 1)) as u32
-                    + pos_set.contains(&(robot.x, robot.y + 1)) as u32
-                    + pos_set.contains(&(robot.x + 1, robot.y + 1)) as u32
+                Direction8::ALL
+                    .iter()
+                    .filter(|direction| pos_set.contains(&(robot.position + direction.offset())))
+                    .count() as u32
             })
             .sum::<u32>()
             / self.robots.len() as u32
     }
-}
 
-impl Display for Map {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let mut map = vec![vec!['.'; self.size.0 as usize]; self.size.1 as usize];
+    /// `true` for every tile with at least one robot.
+    fn tiles(&self) -> Grid<bool> {
+        let mut map = Grid::new(self.size.x as usize, self.size.y as usize, false);
 
         for robot in &self.robots {
-            map[robot.y as usize][robot.x as usize] = '#';
+            map[robot.position] = true;
         }
 
-        for row in map {
-            writeln!(f, "{}", row.iter().collect::<String>())?;
-        }
+        map
+    }
 
-        Ok(())
+    /// Writes the map as image frame `step` if the binary runs with `--dump-frames`, scaled to
+    /// 4 x 4 pixels per tile.
+    fn dump_frame(&self, name: &str, step: usize) {
+        advent_of_code::template::frames::dump(name, step, || {
+            Image::from_grid(&self.tiles(), |&robot| {
+                if robot {
+                    Rgb(0, 200, 0)
+                } else {
+                    Rgb::BLACK
+                }
+            })
+            .scaled(4)
+        });
+    }
+}
+
+impl Display for Map {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let map = self.tiles().map(|&robot| if robot { '#' } else { '.' });
+        write!(f, "{map}")
     }
 }
 
 fn parse_robot(input: &str) -> IResult<&str, Robot> {
-    let (input, _) = tag("p=")(input)?;
-    let (input, x) = digit1(input)?;
-    let (input, _) = tag(",")(input)?;
-    let (input, y) = digit1(input)?;
-    let (input, _) = tag(" v=")(input)?;
-    let (input, v_x) = recognize(preceded(opt(tag("-")), digit1))(input)?;
-    let (input, _) = tag(",")(input)?;
-    let (input, v_y) = recognize(preceded(opt(tag("-")), digit1))(input)?;
-    let (input, _) = tag("\n")(input)?;
+    let (input, position) = labelled("p=", coordinates::<i64>)(input)?;
+    let (input, velocity) = labelled(" v=", coordinates)(input)?;
 
     Ok((
         input,
         Robot {
-            x: x.parse().unwrap(),
-            y: y.parse().unwrap(),
-            v_x: v_x.parse().unwrap(),
-            v_y: v_y.parse().unwrap(),
+            position: Point::from(position),
+            velocity: Vector::from(velocity),
         },
     ))
 }
-fn parse_input(input: &str, size: (i32, i32)) -> Option<Map> {
-    let (_, robots) = nom::multi::many1(parse_robot)(input).ok()?;
-    Some(Map { robots, size })
+fn parse_input(input: &str, size: Vector) -> Result<Map, ParseError> {
+    let robots = parse_lines(input, parse_robot)?;
+    Ok(Map { robots, size })
 }
 
-fn part_one_1(input: &str, size: (i32, i32)) -> Option<u32> {
+fn part_one_1(input: &str, size: Vector) -> Result<u32, ParseError> {
     let mut map = parse_input(input, size)?;
 
-    for _ in 0..100 {
+    map.dump_frame("part1", 0);
+    for i in 0..100 {
         map.step();
+        map.dump_frame("part1", i + 1);
     }
 
-    Some(map.safety_factor())
+    Ok(map.safety_factor())
+}
+
+/// The example is a smaller room than the real puzzle.
+fn map_size() -> Vector {
+    if advent_of_code::template::is_example() {
+        Vector::new(11, 7)
+    } else {
+        Vector::new(101, 103)
+    }
 }
 
-pub fn part_one(input: &str) -> Option<u32> {
-    part_one_1(input, (101, 103))
+pub fn part_one(input: &str) -> Result<u32, ParseError> {
+    part_one_1(input, map_size())
 }
 
-fn part_two_1(input: &str, size: (i32, i32)) -> Option<u32> {
-    let mut map = parse_input(input, size)?;
+fn part_two_1(input: &str, size: Vector) -> Result<u32, String> {
+    let mut map = parse_input(input, size).map_err(|e| e.to_string())?;
 
-    let mut candidates = Vec::new();
+    // The robots repeat their positions after one period, so every picture they can draw shows up
+    // within it.
+    let steps = map.period() as usize;
 
-    for i in 0..10000 {
+    map.dump_frame("part2", 0);
+    for step in 1..=steps {
         map.step();
+        map.dump_frame("part2", step);
         if map.average_robot_density() > 1 {
-            candidates.push(i + 1);
+            advent_of_code::trace!("step {step} is a candidate:\n{map}");
+            return Ok(step as u32);
         }
     }
 
-    candidates.first().copied()
+    Err("no christmas tree found".to_string())
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    part_two_1(input, (101, 103))
+pub fn part_two(input: &str) -> Result<u32, String> {
+    part_two_1(input, map_size())
 }
 
 #[cfg(test)]
@@ -170,17 +199,18 @@ mod tests {
     fn test_part_one() {
         let result = part_one_1(
             &advent_of_code::template::read_file("examples", DAY),
-            (11, 7),
+            Vector::new(11, 7),
         );
-        assert_eq!(result, Some(12));
+        assert_eq!(result, Ok(12));
     }
 
     #[test]
     fn test_part_two() {
         let result = part_two_1(
             &advent_of_code::template::read_file("examples", DAY),
-            (11, 7),
+            Vector::new(11, 7),
         );
-        assert_eq!(result, None);
+        // the example draws no tree, but the robots cluster first after 6 seconds.
+        assert_eq!(result, Ok(6));
     }
 }
@@ -1,106 +1,63 @@
-use std::{
-    fmt::{Display, Formatter},
-    ops::{Add, Sub},
-    str::FromStr,
-};
+use std::fmt::{Display, Formatter};
 
+use advent_of_code::direction::Direction;
+use advent_of_code::grid::Grid;
+use advent_of_code::image::{Image, Rgb};
+use advent_of_code::point::{Point, Vector};
+use advent_of_code::push::{ObjectId, PushGrid};
 use itertools::Itertools;
 
 advent_of_code::solution!(15);
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-struct Position {
-    x: i32,
-    y: i32,
-}
-
-impl Sub for Position {
-    type Output = Position;
-
-    fn sub(self, rhs: Self) -> Self::Output {
-        Position {
-            x: self.x - rhs.x,
-            y: self.y - rhs.y,
-        }
-    }
+enum Square {
+    Wall,
+    BoxL,
+    BoxR,
+    Box,
+    Robot,
+    Empty,
 }
 
-impl Add for Position {
-    type Output = Position;
-
-    fn add(self, rhs: Self) -> Self::Output {
-        Position {
-            x: self.x + rhs.x,
-            y: self.y + rhs.y,
-        }
+impl Display for Square {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let c = match self {
+            Square::Wall => '#',
+            Square::BoxL => '[',
+            Square::BoxR => ']',
+            Square::Robot => '@',
+            Square::Empty => '.',
+            Square::Box => 'O',
+        };
+        write!(f, "{}", c)
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
-
-impl Direction {
-    fn to_position(&self) -> Position {
+impl Square {
+    fn color(&self) -> Rgb {
         match self {
-            Direction::Up => Position { x: 0, y: -1 },
-            Direction::Down => Position { x: 0, y: 1 },
-            Direction::Left => Position { x: -1, y: 0 },
-            Direction::Right => Position { x: 1, y: 0 },
-        }
-    }
-}
-
-impl FromStr for Direction {
-    type Err = String;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "^" => Ok(Direction::Up),
-            "v" => Ok(Direction::Down),
-            "<" => Ok(Direction::Left),
-            ">" => Ok(Direction::Right),
-            _ => Err(format!("Unknown direction: {}", s)),
+            Square::Wall => Rgb(128, 128, 128),
+            Square::BoxL | Square::BoxR | Square::Box => Rgb(160, 110, 40),
+            Square::Robot => Rgb(220, 0, 0),
+            Square::Empty => Rgb::BLACK,
         }
     }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum Square {
-    Wall,
-    BoxL,
-    BoxR,
-    Box,
+enum Kind {
     Robot,
-    Empty,
+    Box,
 }
 
 struct Map {
-    squares: Vec<Vec<Square>>,
-    robot: Position,
+    warehouse: PushGrid<Kind>,
+    robot: ObjectId,
 }
 
 impl Display for Map {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        for row in &self.squares {
-            for square in row {
-                let c = match square {
-                    Square::Wall => '#',
-                    Square::BoxL => '[',
-                    Square::BoxR => ']',
-                    Square::Robot => '@',
-                    Square::Empty => '.',
-                    Square::Box => 'O',
-                };
-                write!(f, "{}", c)?;
-            }
-            writeln!(f)?;
-        }
-        Ok(())
+        write!(f, "{}", self.squares())
     }
 }
 
@@ -110,151 +67,94 @@ enum Version {
     PartTwo,
 }
 
-impl Map {
-    fn parse(s: &str, version: Version) -> Result<Self, String> {
-        let mut squares = Vec::new();
-        let mut robot = Position { x: 0, y: 0 };
-
-        for (y, line) in s.lines().enumerate() {
-            let mut row = Vec::new();
-            for (x, c) in line.chars().enumerate() {
-                let mut squares = match (version, c) {
-                    (Version::PartOne, '#') => vec![Square::Wall],
-                    (Version::PartTwo, '#') => vec![Square::Wall, Square::Wall],
-                    (Version::PartOne, 'O') => vec![Square::Box],
-                    (Version::PartTwo, 'O') => vec![Square::BoxL, Square::BoxR],
-                    (Version::PartOne, '@') => {
-                        robot = Position {
-                            x: x as i32,
-                            y: y as i32,
-                        };
-                        vec![Square::Robot]
-                    }
-                    (Version::PartTwo, '@') => {
-                        robot = Position {
-                            x: (x as i32) * 2,
-                            y: y as i32,
-                        };
-                        vec![Square::Robot, Square::Empty]
-                    }
-                    (Version::PartOne, '.') => vec![Square::Empty],
-                    (Version::PartTwo, '.') => vec![Square::Empty, Square::Empty],
-                    _ => return Err(format!("Unknown character: {}", c)),
-                };
-                row.append(&mut squares);
-            }
-            squares.push(row);
+impl Version {
+    fn name(self) -> &'static str {
+        match self {
+            Version::PartOne => "part1",
+            Version::PartTwo => "part2",
         }
-
-        Ok(Map { squares, robot })
     }
-    fn can_move(&self, direction: Direction, position: Position) -> bool {
-        let move_vector = direction.to_position();
-        let new_position = position + move_vector;
-
-        let current_square = self.squares[position.y as usize][position.x as usize];
 
-        match (direction, current_square) {
-            (Direction::Up | Direction::Down, Square::BoxL) => {
-                self.can_move(direction, new_position)
-                    && self.can_move(direction, new_position + Direction::Right.to_position())
-            }
-            (Direction::Up | Direction::Down, Square::BoxR) => {
-                self.can_move(direction, new_position)
-                    && self.can_move(direction, new_position + Direction::Left.to_position())
-            }
-            (_, Square::BoxL | Square::BoxR) => {
-                self.can_move(direction, new_position + move_vector)
-            }
-            (_, Square::Wall) => false,
-            (_, Square::Empty) => true,
-            _ => self.can_move(direction, new_position),
+    /// In the second version, everything except the robot is twice as wide.
+    fn scale(self) -> usize {
+        match self {
+            Version::PartOne => 1,
+            Version::PartTwo => 2,
         }
     }
+}
 
-    fn try_move_objects(&mut self, direction: Direction, position: Position) {
-        let move_vector = direction.to_position();
-        let new_position = position + move_vector;
-
-        let current_square = self.squares[position.y as usize][position.x as usize];
-
-        match (direction, current_square) {
-            (Direction::Up | Direction::Down, Square::BoxR | Square::BoxL) => {
-                let side = if current_square == Square::BoxL {
-                    Direction::Right
-                } else {
-                    Direction::Left
-                };
-                self.try_move_objects(direction, new_position);
-                self.try_move_objects(direction, new_position + side.to_position());
-
-                let other_square = if current_square == Square::BoxL {
-                    Square::BoxR
-                } else {
-                    Square::BoxL
-                };
-                let other = position + side.to_position();
-                let new_other = other + move_vector;
-
-                self.squares[new_position.y as usize][new_position.x as usize] = current_square;
-                self.squares[new_other.y as usize][new_other.x as usize] = other_square;
-                self.squares[position.y as usize][position.x as usize] = Square::Empty;
-                self.squares[other.y as usize][other.x as usize] = Square::Empty;
-            }
-            (_, Square::BoxL | Square::BoxR) => {
-                self.try_move_objects(direction, new_position);
-
-                self.squares[new_position.y as usize][new_position.x as usize] = current_square;
-                self.squares[position.y as usize][position.x as usize] = Square::Empty;
+impl Map {
+    fn parse(s: &str, version: Version) -> Result<Self, String> {
+        let squares = Grid::try_parse(s, |c| match c {
+            '#' => Some(Square::Wall),
+            'O' => Some(Square::Box),
+            '@' => Some(Square::Robot),
+            '.' => Some(Square::Empty),
+            _ => None,
+        })
+        .map_err(|e| e.to_string())?;
+
+        let scale = version.scale();
+        let box_footprint = (0..scale as i64).map(|x| Vector::new(x, 0)).collect_vec();
+
+        let mut warehouse = PushGrid::new(squares.width() * scale, squares.height());
+        let mut robot = None;
+        for ((x, y), square) in squares.iter() {
+            let position = Point::from((x * scale, y));
+            match square {
+                Square::Wall => (0..scale).for_each(|dx| warehouse.add_wall((x * scale + dx, y))),
+                Square::Box => {
+                    warehouse.add_object(Kind::Box, position, box_footprint.clone());
+                }
+                Square::Robot => {
+                    robot = Some(warehouse.add_object(Kind::Robot, position, vec![Vector::ZERO]));
+                }
+                _ => {}
             }
-            (_, Square::Empty) => {}
-            _ => {
-                self.try_move_objects(direction, new_position);
+        }
 
-                self.squares[new_position.y as usize][new_position.x as usize] = current_square;
-                self.squares[position.y as usize][position.x as usize] = Square::Empty;
+        Ok(Map {
+            warehouse,
+            robot: robot.ok_or("Robot not found")?,
+        })
+    }
 
-                if current_square == Square::Robot {
-                    self.robot = new_position;
+    fn squares(&self) -> Grid<Square> {
+        self.warehouse
+            .render(Square::Wall, Square::Empty, |object, offset| {
+                match (object.kind, object.footprint.len(), offset.x) {
+                    (Kind::Robot, _, _) => Square::Robot,
+                    (Kind::Box, 1, _) => Square::Box,
+                    (Kind::Box, _, 0) => Square::BoxL,
+                    (Kind::Box, _, _) => Square::BoxR,
                 }
-            }
-        }
+            })
     }
 
     fn move_robot(&mut self, direction: Direction) {
-        if !self.can_move(direction, self.robot) {
-            return;
-        }
+        self.warehouse.push(self.robot, direction);
+    }
 
-        self.try_move_objects(direction, self.robot);
+    /// Writes the map as image frame `step` if the binary runs with `--dump-frames`, scaled to
+    /// 4 x 4 pixels per square.
+    fn dump_frame(&self, version: Version, step: usize) {
+        advent_of_code::template::frames::dump(version.name(), step, || {
+            Image::from_grid(&self.squares(), Square::color).scaled(4)
+        });
     }
 
     fn calculate_gps_coords_sum(&self) -> u32 {
-        self.squares
-            .iter()
-            .enumerate()
-            .map(|(x, row)| {
-                row.iter()
-                    .enumerate()
-                    .filter_map(move |(y, &square)| {
-                        if square == Square::Box || square == Square::BoxL {
-                            Some(x * 100 + y)
-                        } else {
-                            None
-                        }
-                    })
-                    .collect_vec()
-            })
-            .flatten()
-            .sum::<usize>() as u32
+        self.warehouse
+            .objects()
+            .filter(|(_, object)| object.kind == Kind::Box)
+            .map(|(_, object)| object.position.y * 100 + object.position.x)
+            .sum::<i64>() as u32
     }
 }
 
 fn parse_instructions(s: &str) -> Vec<Direction> {
-    s.chars()
-        .map(|c| c.to_string().parse())
-        .flatten()
-        .collect_vec()
+    s.chars().flat_map(Direction::try_from).collect_vec()
 }
 
 fn parse_input(s: &str, version: Version) -> (Map, Vec<Direction>) {
@@ -267,8 +167,10 @@ fn parse_input(s: &str, version: Version) -> (Map, Vec<Direction>) {
 pub fn part_one(input: &str) -> Option<u32> {
     let (mut map, instructions) = parse_input(input, Version::PartOne);
 
-    for direction in instructions {
+    map.dump_frame(Version::PartOne, 0);
+    for (i, direction) in instructions.into_iter().enumerate() {
         map.move_robot(direction);
+        map.dump_frame(Version::PartOne, i + 1);
     }
 
     Some(map.calculate_gps_coords_sum())
@@ -277,8 +179,10 @@ pub fn part_one(input: &str) -> Option<u32> {
 pub fn part_two(input: &str) -> Option<u32> {
     let (mut map, instructions) = parse_input(input, Version::PartTwo);
 
-    for direction in instructions {
+    map.dump_frame(Version::PartTwo, 0);
+    for (i, direction) in instructions.into_iter().enumerate() {
         map.move_robot(direction);
+        map.dump_frame(Version::PartTwo, i + 1);
     }
 
     Some(map.calculate_gps_coords_sum())
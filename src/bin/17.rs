@@ -1,14 +1,12 @@
+use advent_of_code::parse::{labelled, list, parse_all, unsigned, ParseError};
 use itertools::Itertools;
 use nom::{
     bytes::complete::tag,
-    character::complete::{digit1, one_of},
-    combinator::map_res,
-    multi::separated_list1,
+    character::complete::{char, one_of},
+    sequence::terminated,
     IResult,
 };
 
-use std::str::FromStr;
-
 advent_of_code::solution!(17);
 
 fn combo_value(operand: u64, a: u64, b: u64, c: u64) -> u64 {
@@ -21,7 +19,7 @@ fn combo_value(operand: u64, a: u64, b: u64, c: u64) -> u64 {
     }
 }
 
-fn run(program: &Vec<u64>, a: u64, b: u64, c: u64) -> Vec<u64> {
+fn run(program: &[u64], a: u64, b: u64, c: u64) -> Vec<u64> {
     let mut pc = 0;
     let mut output = Vec::new();
     let (mut a, mut b, mut c) = (a, b, c);
@@ -37,7 +35,7 @@ fn run(program: &Vec<u64>, a: u64, b: u64, c: u64) -> Vec<u64> {
                 a = a >> combo_value(operand, a, b, c);
             }
             1 => {
-                b = b ^ operand;
+                b ^= operand;
             }
             2 => {
                 b = combo_value(operand, a, b, c) & 0b111;
@@ -48,7 +46,7 @@ fn run(program: &Vec<u64>, a: u64, b: u64, c: u64) -> Vec<u64> {
                 }
             }
             4 => {
-                b = b ^ c;
+                b ^= c;
             }
             5 => {
                 output.push(combo_value(operand, a, b, c) & 0b111);
@@ -69,22 +67,14 @@ fn run(program: &Vec<u64>, a: u64, b: u64, c: u64) -> Vec<u64> {
 fn parse_register(input: &str) -> IResult<&str, u64> {
     let (input, _) = tag("Register ")(input)?;
     let (input, _) = one_of("ABC")(input)?;
-    let (input, _) = tag(": ")(input)?;
-    let (input, value) = digit1(input)?;
-    let (input, _) = tag("\n")(input)?;
-
-    Ok((input, value.parse().unwrap()))
+    terminated(labelled(": ", unsigned), char('\n'))(input)
 }
 
 fn parse_program(input: &str) -> IResult<&str, Vec<u64>> {
-    let (input, _) = tag("Program: ")(input)?;
-
-    let (input, program) = separated_list1(tag(","), map_res(digit1, u64::from_str))(input)?;
-
-    Ok((input, program))
+    terminated(labelled("Program: ", list(",", unsigned)), char('\n'))(input)
 }
 
-fn parse(input: &str) -> IResult<&str, (Vec<u64>, u64, u64, u64)> {
+fn parse_computer(input: &str) -> IResult<&str, (Vec<u64>, u64, u64, u64)> {
     let (input, a) = parse_register(input)?;
     let (input, b) = parse_register(input)?;
     let (input, c) = parse_register(input)?;
@@ -96,18 +86,22 @@ fn parse(input: &str) -> IResult<&str, (Vec<u64>, u64, u64, u64)> {
     Ok((input, (program, a, b, c)))
 }
 
-pub fn part_one(input: &str) -> Option<String> {
-    let (program, a, b, c) = parse(input).ok()?.1;
+fn parse(input: &str) -> Result<(Vec<u64>, u64, u64, u64), ParseError> {
+    parse_all(input, parse_computer)
+}
+
+pub fn part_one(input: &str) -> Result<String, ParseError> {
+    let (program, a, b, c) = parse(input)?;
     let output = run(&program, a, b, c);
 
-    Some(output.iter().map(ToString::to_string).join(","))
+    Ok(output.iter().map(ToString::to_string).join(","))
 }
 
 fn find_next(program: &Vec<u64>, expect: Vec<u64>, num: u64) -> Option<u64> {
     let possible_nexts = (0..8)
         .filter_map(|a| {
             let a = num << 3 | a;
-            let res = run(&program, a, 0, 0);
+            let res = run(program, a, 0, 0);
 
             if res == expect {
                 Some(a)
@@ -121,7 +115,7 @@ fn find_next(program: &Vec<u64>, expect: Vec<u64>, num: u64) -> Option<u64> {
         return possible_nexts
             .iter()
             .filter_map(|possible| {
-                let res = run(&program, num << 3 | *possible, 0, 0);
+                let res = run(program, num << 3 | *possible, 0, 0);
 
                 if res == expect {
                     Some(*possible)
@@ -132,7 +126,7 @@ fn find_next(program: &Vec<u64>, expect: Vec<u64>, num: u64) -> Option<u64> {
             .next();
     }
 
-    let next_expect = vec![
+    let next_expect = [
         vec![program[program.len() - expect.len() - 1]],
         expect.clone(),
     ]
@@ -146,10 +140,11 @@ fn find_next(program: &Vec<u64>, expect: Vec<u64>, num: u64) -> Option<u64> {
         .flatten()
 }
 
-pub fn part_two(input: &str) -> Option<u64> {
-    let (program, _, _, _) = parse(input).ok()?.1;
+pub fn part_two(input: &str) -> Result<u64, String> {
+    let (program, _, _, _) = parse(input).map_err(|e| e.to_string())?;
 
     find_next(&program, vec![program[program.len() - 1]], 0)
+        .ok_or_else(|| "no value of register A outputs the program".to_string())
 }
 
 #[cfg(test)]
@@ -158,13 +153,15 @@ mod tests {
 
     #[test]
     fn test_part_one() {
-        let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some("4,6,3,5,6,3,5,2,1,0".to_string()));
+        let result = part_one(&advent_of_code::template::read_file_part(
+            "examples", DAY, 1,
+        ));
+        assert_eq!(result, Ok("4,6,3,5,6,3,5,2,1,0".to_string()));
     }
 
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(117440));
+        assert_eq!(result, Ok(117440));
     }
 }
@@ -1,6 +1,7 @@
-use std::{cmp::Ordering, collections::BinaryHeap};
-
-use nom::{bytes::complete::tag, character::complete::digit1, multi::many1, IResult};
+use advent_of_code::grid::Grid;
+use advent_of_code::image::{Image, Rgb};
+use advent_of_code::parse::{coordinates, parse_lines, ParseError};
+use advent_of_code::search::bfs;
 
 advent_of_code::solution!(18);
 
@@ -8,141 +9,85 @@ advent_of_code::solution!(18);
 enum Square {
     Open,
     Corrupted,
-    Visited(u32),
 }
 
-fn parse_block(input: &str) -> IResult<&str, (usize, usize)> {
-    let (input, x) = digit1(input)?;
-    let (input, _) = tag(",")(input)?;
-    let (input, y) = digit1(input)?;
-    let (input, _) = tag("\n")(input)?;
-
-    Ok((input, (x.parse().unwrap(), y.parse().unwrap())))
-}
-
-fn parse_falling_blocks(input: &str) -> IResult<&str, Vec<(usize, usize)>> {
-    let (input, blocks) = many1(parse_block)(input)?;
-
-    Ok((input, blocks))
-}
-
-fn should_visit(square: Square, steps: u32) -> bool {
-    match square {
-        Square::Visited(visited_steps) => visited_steps > steps,
-        Square::Open => true,
-        _ => false,
+impl Square {
+    fn color(&self) -> Rgb {
+        match self {
+            Square::Open => Rgb::BLACK,
+            Square::Corrupted => Rgb(200, 0, 0),
+        }
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-struct State {
-    steps: u32,
-    current: (usize, usize),
-}
-
-// The priority queue depends on `Ord`.
-// Explicitly implement the trait so the queue becomes a min-heap
-// instead of a max-heap.
-impl Ord for State {
-    fn cmp(&self, other: &Self) -> Ordering {
-        // Notice that we flip the ordering on costs.
-        // In case of a tie we compare positions - this step is necessary
-        // to make implementations of `PartialEq` and `Ord` consistent.
-        other.steps.cmp(&self.steps)
-    }
+/// Writes the map as image frame `step` if the binary runs with `--dump-frames`, scaled to
+/// 4 x 4 pixels per square.
+fn dump_frame(name: &str, step: usize, map: &Grid<Square>) {
+    advent_of_code::template::frames::dump(name, step, || {
+        Image::from_grid(map, Square::color).scaled(4)
+    });
 }
 
-// `PartialOrd` needs to be implemented as well.
-impl PartialOrd for State {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
+fn parse_falling_blocks(input: &str) -> Result<Vec<(usize, usize)>, ParseError> {
+    parse_lines(input, coordinates)
 }
 
-fn steps_to_goal(
-    map: &mut Vec<Vec<Square>>,
-    map_size: usize,
-    start: (usize, usize),
-    end: (usize, usize),
-) -> Option<u32> {
-    let mut queue = BinaryHeap::new();
-    queue.push(State {
-        steps: 0,
-        current: start,
-    });
-
-    while let Some(State { current, steps }) = queue.pop() {
-        if current == end {
-            return Some(steps);
-        }
+fn steps_to_goal(map: &Grid<Square>, start: (usize, usize), end: (usize, usize)) -> Option<u32> {
+    let path = bfs(
+        start,
+        |&pos| map.neighbours_4(pos).filter(|n| map[*n] == Square::Open),
+        |&pos| pos == end,
+    )?;
 
-        let (row, col) = current;
-
-        if row > 0 && should_visit(map[row - 1][col], steps + 1) {
-            map[row - 1][col] = Square::Visited(steps + 1);
-            queue.push(State {
-                steps: steps + 1,
-                current: (row - 1, col),
-            });
-        }
-
-        if row < map_size - 1 && should_visit(map[row + 1][col], steps + 1) {
-            map[row + 1][col] = Square::Visited(steps + 1);
-            queue.push(State {
-                steps: steps + 1,
-                current: (row + 1, col),
-            });
-        }
-
-        if col > 0 && should_visit(map[row][col - 1], steps + 1) {
-            map[row][col - 1] = Square::Visited(steps + 1);
-            queue.push(State {
-                steps: steps + 1,
-                current: (row, col - 1),
-            });
-        }
-
-        if col < map_size - 1 && should_visit(map[row][col + 1], steps + 1) {
-            map[row][col + 1] = Square::Visited(steps + 1);
-            queue.push(State {
-                steps: steps + 1,
-                current: (row, col + 1),
-            });
-        }
-    }
-
-    None
+    Some(path.cost as u32)
 }
 
-fn part_one_solve(input: &str, map_size: usize, take_size: usize) -> Option<u32> {
-    let (_, blocks) = parse_falling_blocks(input).unwrap();
+fn part_one_solve(input: &str, map_size: usize, take_size: usize) -> Result<u32, String> {
+    let blocks = parse_falling_blocks(input).map_err(|e| e.to_string())?;
 
-    let mut map = vec![vec![Square::Open; map_size]; map_size];
+    let mut map = Grid::new(map_size, map_size, Square::Open);
 
-    for &(col, row) in blocks.iter().take(take_size) {
-        map[row][col] = Square::Corrupted;
+    dump_frame("part1", 0, &map);
+    for (i, &block) in blocks.iter().take(take_size).enumerate() {
+        map[block] = Square::Corrupted;
+        dump_frame("part1", i + 1, &map);
     }
 
     let start: (usize, usize) = (0, 0);
     let end: (usize, usize) = (map_size - 1, map_size - 1);
 
-    steps_to_goal(&mut map, map_size, start, end)
+    steps_to_goal(&map, start, end).ok_or_else(|| "no path to the exit".to_string())
+}
+
+/// The example uses a smaller memory space and fewer fallen bytes than the real puzzle.
+fn parameters() -> (usize, usize) {
+    if advent_of_code::template::is_example() {
+        (7, 12)
+    } else {
+        (71, 1024)
+    }
 }
 
-pub fn part_one(input: &str) -> Option<u32> {
-    part_one_solve(input, 71, 1024)
+pub fn part_one(input: &str) -> Result<u32, String> {
+    let (map_size, take_size) = parameters();
+    part_one_solve(input, map_size, take_size)
 }
 
-fn part_two_solve(input: &str, map_size: usize, take_size: usize) -> Option<String> {
-    let (_, blocks) = parse_falling_blocks(input).unwrap();
+fn part_two_solve(input: &str, map_size: usize, take_size: usize) -> Result<String, String> {
+    let blocks = parse_falling_blocks(input).map_err(|e| e.to_string())?;
 
-    let mut map = vec![vec![Square::Open; map_size]; map_size];
+    let mut map = Grid::new(map_size, map_size, Square::Open);
 
     let mut blocks_iter = blocks.iter();
 
+    let mut step = 0;
+    dump_frame("part2", step, &map);
+
     for _ in 0..take_size {
-        if let Some(&(col, row)) = blocks_iter.next() {
-            map[row][col] = Square::Corrupted;
+        if let Some(&block) = blocks_iter.next() {
+            map[block] = Square::Corrupted;
+            step += 1;
+            dump_frame("part2", step, &map);
         }
     }
 
@@ -151,30 +96,23 @@ fn part_two_solve(input: &str, map_size: usize, take_size: usize) -> Option<Stri
 
     let mut last_block = (0, 0);
 
-    while let Some(_) = steps_to_goal(&mut map, map_size, start, end) {
-        if let Some(&(col, row)) = blocks_iter.next() {
-            map[row][col] = Square::Corrupted;
-            last_block = (col, row);
-
-            // Reset the map
-
-            for row in map.iter_mut() {
-                for square in row.iter_mut() {
-                    if let Square::Visited(_) = square {
-                        *square = Square::Open;
-                    }
-                }
-            }
+    while steps_to_goal(&map, start, end).is_some() {
+        if let Some(&block) = blocks_iter.next() {
+            map[block] = Square::Corrupted;
+            last_block = block;
+            step += 1;
+            dump_frame("part2", step, &map);
         } else {
-            panic!("Ran out of blocks");
+            return Err("ran out of blocks".to_string());
         }
     }
 
-    Some(format!("{},{}", last_block.0, last_block.1))
+    Ok(format!("{},{}", last_block.0, last_block.1))
 }
 
-pub fn part_two(input: &str) -> Option<String> {
-    part_two_solve(input, 71, 1024)
+pub fn part_two(input: &str) -> Result<String, String> {
+    let (map_size, take_size) = parameters();
+    part_two_solve(input, map_size, take_size)
 }
 
 #[cfg(test)]
@@ -184,12 +122,12 @@ mod tests {
     #[test]
     fn test_part_one() {
         let result = part_one_solve(&advent_of_code::template::read_file("examples", DAY), 7, 12);
-        assert_eq!(result, Some(22));
+        assert_eq!(result, Ok(22));
     }
 
     #[test]
     fn test_part_two() {
         let result = part_two_solve(&advent_of_code::template::read_file("examples", DAY), 7, 12);
-        assert_eq!(result, Some("6,1".to_owned()));
+        assert_eq!(result, Ok("6,1".to_owned()));
     }
 }
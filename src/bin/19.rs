@@ -1,13 +1,15 @@
 use std::collections::HashMap;
+use std::hash::BuildHasher;
 
+use advent_of_code::hash::{FastMap, HashMapExt};
 use itertools::Itertools;
 
-advent_of_code::solution!(19);
+advent_of_code::solution!(19, variants: [part_two_sip_hash: 2]);
 
-fn can_make_pattern<'a>(
+fn can_make_pattern<'a, S: BuildHasher>(
     pattern: &'a str,
     towels: &Vec<&str>,
-    memo: &mut HashMap<&'a str, u64>,
+    memo: &mut HashMap<&'a str, u64, S>,
 ) -> u64 {
     if pattern.is_empty() {
         return 1;
@@ -31,7 +33,7 @@ pub fn part_one(input: &str) -> Option<u32> {
     let towels = towels.split(", ").collect();
     let patterns: Vec<&str> = patterns.lines().collect();
 
-    let mut memo = HashMap::new();
+    let mut memo = FastMap::new();
 
     patterns
         .iter()
@@ -41,19 +43,29 @@ pub fn part_one(input: &str) -> Option<u32> {
         .ok()
 }
 
-pub fn part_two(input: &str) -> Option<u64> {
+fn count_arrangements<'a, S: BuildHasher>(
+    input: &'a str,
+    mut memo: HashMap<&'a str, u64, S>,
+) -> Option<u64> {
     let (towels, patterns) = input.split("\n\n").collect_tuple().unwrap();
     let towels = towels.split(", ").collect();
     let patterns: Vec<&str> = patterns.lines().collect();
 
-    let mut memo = HashMap::new();
-
     patterns
         .iter()
         .map(|pattern| can_make_pattern(pattern, &towels, &mut memo))
         .sum1()
 }
 
+pub fn part_two(input: &str) -> Option<u64> {
+    count_arrangements(input, FastMap::new())
+}
+
+/// Same as `part_two` with the default SipHash hasher, to compare with `cargo time 19 --variants`
+pub fn part_two_sip_hash(input: &str) -> Option<u64> {
+    count_arrangements(input, HashMap::new())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -69,4 +81,10 @@ mod tests {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
         assert_eq!(result, Some(16));
     }
+
+    #[test]
+    fn test_part_two_sip_hash() {
+        let result = part_two_sip_hash(&advent_of_code::template::read_file("examples", DAY));
+        assert_eq!(result, Some(16));
+    }
 }
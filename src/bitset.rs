@@ -0,0 +1,290 @@
+//! Compact sets of small integers and grid positions, e.g. for the visited set of a search that
+//! runs many times on the same map.
+//!
+//! Both sets can be cleared in O(1): every word of bits carries the generation in which it was
+//! last written, and words from older generations count as empty.
+
+/// A set of integers in `0..capacity`.
+#[derive(Debug, Clone)]
+pub struct BitSet {
+    capacity: usize,
+    words: Vec<u64>,
+    generations: Vec<u32>,
+    generation: u32,
+}
+
+impl BitSet {
+    /// Creates an empty set for the integers in `0..capacity`.
+    pub fn new(capacity: usize) -> Self {
+        let words = capacity.div_ceil(64);
+        Self {
+            capacity,
+            words: vec![0; words],
+            generations: vec![0; words],
+            generation: 1,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    fn word(&self, index: usize) -> u64 {
+        if self.generations[index] == self.generation {
+            self.words[index]
+        } else {
+            0
+        }
+    }
+
+    fn word_mut(&mut self, index: usize) -> &mut u64 {
+        if self.generations[index] != self.generation {
+            self.generations[index] = self.generation;
+            self.words[index] = 0;
+        }
+        &mut self.words[index]
+    }
+
+    fn check(&self, value: usize) {
+        assert!(
+            value < self.capacity,
+            "{value} out of bounds of set with capacity {}",
+            self.capacity
+        );
+    }
+
+    /// Adds `value`. Returns `true` if it was not in the set yet.
+    ///
+    /// # Panics
+    /// If `value` is not below the capacity.
+    pub fn insert(&mut self, value: usize) -> bool {
+        self.check(value);
+        let word = self.word_mut(value / 64);
+        let bit = 1 << (value % 64);
+        let inserted = *word & bit == 0;
+        *word |= bit;
+        inserted
+    }
+
+    /// Removes `value`. Returns `true` if it was in the set.
+    pub fn remove(&mut self, value: usize) -> bool {
+        self.check(value);
+        let word = self.word_mut(value / 64);
+        let bit = 1 << (value % 64);
+        let removed = *word & bit != 0;
+        *word &= !bit;
+        removed
+    }
+
+    pub fn contains(&self, value: usize) -> bool {
+        self.check(value);
+        self.word(value / 64) & (1 << (value % 64)) != 0
+    }
+
+    /// Removes all values in O(1).
+    pub fn clear(&mut self) {
+        self.generation = self.generation.wrapping_add(1);
+        if self.generation == 0 {
+            // stale words might look current after the counter wrapped around.
+            self.generations.fill(0);
+            self.generation = 1;
+        }
+    }
+
+    /// Number of values in the set.
+    pub fn len(&self) -> usize {
+        (0..self.words.len())
+            .map(|index| self.word(index).count_ones() as usize)
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        (0..self.words.len()).all(|index| self.word(index) == 0)
+    }
+
+    /// All values in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.words.len()).flat_map(move |index| {
+            let mut word = self.word(index);
+            std::iter::from_fn(move || {
+                (word != 0).then(|| {
+                    let bit = word.trailing_zeros() as usize;
+                    word &= word - 1;
+                    index * 64 + bit
+                })
+            })
+        })
+    }
+}
+
+/// A set of `(x, y)` positions on a `width` x `height` grid, with an optional extra dimension of
+/// `layers` per position, e.g. one layer per [`crate::direction::Direction`] to remember in
+/// which direction a cell was entered.
+#[derive(Debug, Clone)]
+pub struct BitGrid {
+    width: usize,
+    height: usize,
+    layers: usize,
+    bits: BitSet,
+}
+
+impl BitGrid {
+    /// Creates an empty set of positions.
+    pub fn new(width: usize, height: usize) -> Self {
+        Self::with_layers(width, height, 1)
+    }
+
+    /// Creates an empty set of `(position, layer)` pairs with `layer` in `0..layers`.
+    pub fn with_layers(width: usize, height: usize, layers: usize) -> Self {
+        Self {
+            width,
+            height,
+            layers,
+            bits: BitSet::new(width * height * layers),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    fn index(&self, (x, y): (usize, usize), layer: usize) -> usize {
+        assert!(
+            x < self.width && y < self.height && layer < self.layers,
+            "position {:?} in layer {layer} out of bounds of {}x{}x{} grid",
+            (x, y),
+            self.width,
+            self.height,
+            self.layers
+        );
+        ((y * self.width) + x) * self.layers + layer
+    }
+
+    /// Adds `position` to the first layer. Returns `true` if it was not in the set yet.
+    ///
+    /// # Panics
+    /// If `position` lies outside of the grid.
+    pub fn insert(&mut self, position: (usize, usize)) -> bool {
+        self.insert_in_layer(position, 0)
+    }
+
+    pub fn contains(&self, position: (usize, usize)) -> bool {
+        self.contains_in_layer(position, 0)
+    }
+
+    pub fn remove(&mut self, position: (usize, usize)) -> bool {
+        self.remove_from_layer(position, 0)
+    }
+
+    /// Adds `position` to `layer`. Returns `true` if it was not in the set yet.
+    ///
+    /// # Panics
+    /// If `position` lies outside of the grid or `layer` is not below the number of layers.
+    pub fn insert_in_layer(&mut self, position: (usize, usize), layer: usize) -> bool {
+        let index = self.index(position, layer);
+        self.bits.insert(index)
+    }
+
+    pub fn contains_in_layer(&self, position: (usize, usize), layer: usize) -> bool {
+        self.bits.contains(self.index(position, layer))
+    }
+
+    pub fn remove_from_layer(&mut self, position: (usize, usize), layer: usize) -> bool {
+        let index = self.index(position, layer);
+        self.bits.remove(index)
+    }
+
+    /// Removes all positions from all layers in O(1).
+    pub fn clear(&mut self) {
+        self.bits.clear();
+    }
+
+    /// Number of `(position, layer)` pairs in the set.
+    pub fn len(&self) -> usize {
+        self.bits.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bits.is_empty()
+    }
+
+    /// All positions that are in the set in any layer, in row-major order.
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let mut last = None;
+        self.bits.iter().filter_map(move |index| {
+            let cell = index / self.layers;
+            (last != Some(cell)).then(|| {
+                last = Some(cell);
+                (cell % self.width, cell / self.width)
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BitGrid, BitSet};
+
+    #[test]
+    fn inserts_and_removes_values() {
+        let mut set = BitSet::new(200);
+        assert!(set.is_empty());
+        assert!(set.insert(3));
+        assert!(!set.insert(3));
+        assert!(set.insert(64));
+        assert!(set.insert(199));
+        assert!(set.contains(64));
+        assert!(!set.contains(65));
+        assert_eq!(set.len(), 3);
+
+        assert!(set.remove(64));
+        assert!(!set.remove(64));
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![3, 199]);
+    }
+
+    #[test]
+    fn clears_by_generation() {
+        let mut set = BitSet::new(100);
+        set.insert(10);
+        set.insert(90);
+        set.clear();
+        assert!(set.is_empty());
+        assert!(!set.contains(10));
+
+        // a word from an old generation is reset on write.
+        assert!(set.insert(11));
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![11]);
+
+        set.generation = u32::MAX;
+        set.insert(5);
+        set.clear();
+        assert!(!set.contains(5));
+        assert!(!set.contains(11));
+    }
+
+    #[test]
+    #[should_panic]
+    fn rejects_values_out_of_bounds() {
+        BitSet::new(10).insert(10);
+    }
+
+    #[test]
+    fn stores_positions_in_layers() {
+        let mut grid = BitGrid::with_layers(3, 2, 4);
+        assert!(grid.insert_in_layer((2, 1), 0));
+        assert!(grid.insert_in_layer((2, 1), 3));
+        assert!(!grid.insert_in_layer((2, 1), 3));
+        assert!(grid.insert((0, 1)));
+        assert!(!grid.contains_in_layer((2, 1), 1));
+        assert_eq!(grid.len(), 3);
+        assert_eq!(grid.positions().collect::<Vec<_>>(), vec![(0, 1), (2, 1)]);
+
+        grid.clear();
+        assert!(grid.is_empty());
+        assert!(!grid.contains((0, 1)));
+    }
+}
@@ -0,0 +1,176 @@
+//! A multiset that counts how often each value occurs, e.g. for puzzles that only care about the
+//! number of equal items in a population.
+
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hash};
+use std::mem::swap;
+
+use crate::hash::FastBuildHasher;
+
+/// Counts of values of type `T`, hashed with [`crate::hash::FastHasher`] by default.
+#[derive(Debug, Clone)]
+pub struct Counter<T, S = FastBuildHasher> {
+    counts: HashMap<T, u64, S>,
+    // the next generation in `transform`, kept to reuse its allocation.
+    buffer: HashMap<T, u64, S>,
+}
+
+impl<T: Eq + Hash> Counter<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<T: Eq + Hash, S: BuildHasher + Default> Counter<T, S> {
+    /// Creates a counter from `(value, count)` pairs. Counts of equal values are added up.
+    pub fn from_counts(counts: impl IntoIterator<Item = (T, u64)>) -> Self {
+        let mut counter = Self::default();
+        for (value, count) in counts {
+            counter.add(value, count);
+        }
+        counter
+    }
+
+    /// Adds `count` occurrences of `value`.
+    pub fn add(&mut self, value: T, count: u64) {
+        if count > 0 {
+            *self.counts.entry(value).or_default() += count;
+        }
+    }
+
+    /// How often `value` occurs, `0` for unknown values.
+    pub fn get(&self, value: &T) -> u64 {
+        self.counts.get(value).copied().unwrap_or_default()
+    }
+
+    /// All distinct values with their counts, in arbitrary order.
+    pub fn iter(&self) -> impl Iterator<Item = (&T, u64)> {
+        self.counts.iter().map(|(value, &count)| (value, count))
+    }
+
+    /// Number of distinct values.
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /// Sum of all counts.
+    pub fn total(&self) -> u64 {
+        self.counts.values().sum()
+    }
+
+    /// Adds all counts of `other`.
+    pub fn merge(&mut self, other: Counter<T, S>) {
+        for (value, count) in other.counts {
+            self.add(value, count);
+        }
+    }
+
+    /// Replaces every value by the values that `rule` adds to the next generation, e.g. for
+    /// simulations of populations in which equal members evolve the same way.
+    ///
+    /// `rule` is called once per distinct value with its count and the counter of the next
+    /// generation. The two generations are double-buffered, so repeated steps do not allocate.
+    pub fn transform(&mut self, mut rule: impl FnMut(&T, u64, &mut Counter<T, S>)) {
+        let mut next = Counter {
+            counts: std::mem::take(&mut self.buffer),
+            buffer: HashMap::default(),
+        };
+        for (value, &count) in &self.counts {
+            rule(value, count, &mut next);
+        }
+
+        swap(&mut self.counts, &mut next.counts);
+        self.buffer = next.counts;
+        self.buffer.clear();
+    }
+}
+
+impl<T: Eq + Hash, S: BuildHasher + Default> Default for Counter<T, S> {
+    fn default() -> Self {
+        Self {
+            counts: HashMap::default(),
+            buffer: HashMap::default(),
+        }
+    }
+}
+
+impl<T: Eq + Hash, S: BuildHasher> PartialEq for Counter<T, S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.counts == other.counts
+    }
+}
+
+impl<T: Eq + Hash, S: BuildHasher> Eq for Counter<T, S> {}
+
+/// Counts every value once.
+impl<T: Eq + Hash, S: BuildHasher + Default> FromIterator<T> for Counter<T, S> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut counter = Self::default();
+        counter.extend(iter);
+        counter
+    }
+}
+
+impl<T: Eq + Hash, S: BuildHasher + Default> Extend<T> for Counter<T, S> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.add(value, 1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Counter;
+
+    #[test]
+    fn counts_values() {
+        let mut counter: Counter<char> = "abracadabra".chars().collect();
+        assert_eq!(counter.get(&'a'), 5);
+        assert_eq!(counter.get(&'z'), 0);
+        assert_eq!((counter.len(), counter.total()), (5, 11));
+
+        counter.add('z', 3);
+        counter.add('y', 0);
+        assert_eq!(counter.get(&'z'), 3);
+        assert_eq!(counter.len(), 6);
+
+        let mut counts: Vec<_> = counter.iter().map(|(&c, n)| (c, n)).collect();
+        counts.sort();
+        assert_eq!(
+            counts,
+            vec![('a', 5), ('b', 2), ('c', 1), ('d', 1), ('r', 2), ('z', 3)]
+        );
+    }
+
+    #[test]
+    fn merges_counters() {
+        let mut counter: Counter<u32> = Counter::from_counts([(1, 2), (2, 1), (1, 1)]);
+        assert_eq!(counter.get(&1), 3);
+
+        counter.merge([2, 3, 3].into_iter().collect());
+        assert_eq!(counter, Counter::from_counts([(1, 3), (2, 2), (3, 2)]));
+    }
+
+    #[test]
+    fn transforms_generations() {
+        // every value n splits into n - 1 and n - 2 while positive.
+        let mut counter: Counter<u32> = [4].into_iter().collect();
+        for _ in 0..3 {
+            counter.transform(|&n, count, next| match n {
+                0 | 1 => next.add(0, count),
+                n => {
+                    next.add(n - 1, count);
+                    next.add(n - 2, count);
+                }
+            });
+        }
+
+        assert_eq!(counter, Counter::from_counts([(1, 1), (0, 4)]));
+        assert_eq!(counter.total(), 5);
+    }
+}
@@ -0,0 +1,326 @@
+//! Randomized cross-checking of two implementations against each other,
+//! e.g. an optimized solution against a brute-force oracle.
+
+use std::fmt::Debug;
+use std::panic::{self, AssertUnwindSafe};
+
+/// A small deterministic PRNG (SplitMix64). Not suitable for cryptographic use.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a uniformly distributed value in `min..=max`.
+    pub fn range(&mut self, min: i64, max: i64) -> i64 {
+        assert!(min <= max, "invalid range {min}..={max}");
+        let span = max.abs_diff(min).wrapping_add(1);
+        if span == 0 {
+            // the range covers all of i64.
+            return self.next_u64() as i64;
+        }
+        min.wrapping_add((self.next_u64() % span) as i64)
+    }
+
+    /// Returns a uniformly distributed index in `0..len`.
+    pub fn index(&mut self, len: usize) -> usize {
+        assert!(len > 0, "can not pick an index of an empty collection");
+        (self.next_u64() % len as u64) as usize
+    }
+
+    /// Returns `true` with a probability of `p`.
+    pub fn chance(&mut self, p: f64) -> bool {
+        let sample = (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64;
+        sample < p
+    }
+
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.index(items.len())]
+    }
+}
+
+/// Types that can produce "smaller" variants of themselves, used to minimize failing inputs.
+pub trait Shrink: Sized {
+    /// Candidates that are strictly smaller than `self`. Simpler candidates come first.
+    fn shrink(&self) -> Vec<Self>;
+}
+
+macro_rules! impl_shrink_unsigned {
+    ($($t:ty),*) => {
+        $(impl Shrink for $t {
+            fn shrink(&self) -> Vec<Self> {
+                let mut candidates = vec![];
+                if *self != 0 {
+                    candidates.extend([0, *self / 2, *self - 1]);
+                }
+                candidates.dedup();
+                candidates
+            }
+        })*
+    };
+}
+
+macro_rules! impl_shrink_signed {
+    ($($t:ty),*) => {
+        $(impl Shrink for $t {
+            fn shrink(&self) -> Vec<Self> {
+                let mut candidates = vec![];
+                if *self != 0 {
+                    candidates.extend([0, *self / 2]);
+                    if let Some(positive) = self.checked_neg().filter(|x| *x > 0) {
+                        candidates.push(positive);
+                    }
+                    candidates.push(*self - self.signum());
+                }
+                candidates.dedup();
+                candidates
+            }
+        })*
+    };
+}
+
+impl_shrink_unsigned!(u8, u16, u32, u64, u128, usize);
+impl_shrink_signed!(i8, i16, i32, i64, i128, isize);
+
+impl Shrink for bool {
+    fn shrink(&self) -> Vec<Self> {
+        if *self {
+            vec![false]
+        } else {
+            vec![]
+        }
+    }
+}
+
+impl Shrink for char {
+    fn shrink(&self) -> Vec<Self> {
+        ['a', '0', ' '].into_iter().filter(|c| c < self).collect()
+    }
+}
+
+impl<T: Shrink + Clone> Shrink for Vec<T> {
+    fn shrink(&self) -> Vec<Self> {
+        let mut candidates = vec![];
+
+        // drop halves first to quickly get rid of large irrelevant chunks.
+        if self.len() > 1 {
+            let half = self.len() / 2;
+            candidates.push(self[..half].to_vec());
+            candidates.push(self[half..].to_vec());
+        }
+
+        for i in 0..self.len() {
+            let mut candidate = self.clone();
+            candidate.remove(i);
+            candidates.push(candidate);
+        }
+
+        for (i, item) in self.iter().enumerate() {
+            for smaller in item.shrink() {
+                let mut candidate = self.clone();
+                candidate[i] = smaller;
+                candidates.push(candidate);
+            }
+        }
+
+        candidates
+    }
+}
+
+impl Shrink for String {
+    fn shrink(&self) -> Vec<Self> {
+        self.chars()
+            .collect::<Vec<_>>()
+            .shrink()
+            .into_iter()
+            .map(|chars| chars.into_iter().collect())
+            .collect()
+    }
+}
+
+impl<A: Shrink + Clone, B: Shrink + Clone> Shrink for (A, B) {
+    fn shrink(&self) -> Vec<Self> {
+        let first = self.0.shrink().into_iter().map(|a| (a, self.1.clone()));
+        let second = self.1.shrink().into_iter().map(|b| (self.0.clone(), b));
+        first.chain(second).collect()
+    }
+}
+
+/// Result of calling an implementation. Panics are recorded so that they count as a mismatch
+/// against an implementation that returns normally.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome<O> {
+    Returned(O),
+    Panicked,
+}
+
+fn call<T, O>(func: &impl Fn(&T) -> O, input: &T) -> Outcome<O> {
+    panic::catch_unwind(AssertUnwindSafe(|| func(input)))
+        .map_or(Outcome::Panicked, Outcome::Returned)
+}
+
+/// An input for which two implementations disagree.
+#[derive(Debug, Clone)]
+pub struct Mismatch<T, O> {
+    /// Seed of the generator run that produced the failing input.
+    pub seed: u64,
+    pub original: T,
+    /// The smallest failing input found by shrinking `original`.
+    pub shrunk: T,
+    pub left: Outcome<O>,
+    pub right: Outcome<O>,
+}
+
+/// Runs `left` and `right` on inputs produced by `generate` for the seeds `0..seeds` and compares
+/// their results. On the first mismatch, the input is shrunk to a minimal failing case.
+pub fn cross_check<T, O>(
+    seeds: u64,
+    generate: impl Fn(&mut Rng) -> T,
+    left: impl Fn(&T) -> O,
+    right: impl Fn(&T) -> O,
+) -> Result<(), Mismatch<T, O>>
+where
+    T: Shrink + Clone,
+    O: PartialEq,
+{
+    let disagree = |input: &T| {
+        let (l, r) = (call(&left, input), call(&right, input));
+        (l != r).then_some((l, r))
+    };
+
+    for seed in 0..seeds {
+        let original = generate(&mut Rng::new(seed));
+
+        let Some(mut outcomes) = disagree(&original) else {
+            continue;
+        };
+
+        let mut shrunk = original.clone();
+        'shrink: loop {
+            for candidate in shrunk.shrink() {
+                if let Some(candidate_outcomes) = disagree(&candidate) {
+                    shrunk = candidate;
+                    outcomes = candidate_outcomes;
+                    continue 'shrink;
+                }
+            }
+            break;
+        }
+
+        return Err(Mismatch {
+            seed,
+            original,
+            shrunk,
+            left: outcomes.0,
+            right: outcomes.1,
+        });
+    }
+
+    Ok(())
+}
+
+/// Like [`cross_check`], but panics with a description of the minimal failing input.
+/// Intended for use in tests.
+pub fn assert_cross_check<T, O>(
+    seeds: u64,
+    generate: impl Fn(&mut Rng) -> T,
+    left: impl Fn(&T) -> O,
+    right: impl Fn(&T) -> O,
+) where
+    T: Shrink + Clone + Debug,
+    O: PartialEq + Debug,
+{
+    if let Err(mismatch) = cross_check(seeds, generate, left, right) {
+        panic!(
+            "implementations disagree for seed {}\n  input: {:?}\n  left: {:?}\n  right: {:?}",
+            mismatch.seed, mismatch.shrunk, mismatch.left, mismatch.right
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{cross_check, Outcome, Rng, Shrink};
+
+    #[test]
+    fn rng_is_deterministic() {
+        let (mut a, mut b) = (Rng::new(42), Rng::new(42));
+        assert!((0..100).all(|_| a.next_u64() == b.next_u64()));
+    }
+
+    #[test]
+    fn rng_range_is_inclusive() {
+        let mut rng = Rng::new(1);
+        let values: Vec<i64> = (0..1000).map(|_| rng.range(-2, 2)).collect();
+        assert!(values.iter().all(|v| (-2..=2).contains(v)));
+        assert!(values.contains(&-2) && values.contains(&2));
+    }
+
+    #[test]
+    fn shrinks_integers_towards_zero() {
+        assert_eq!(10u32.shrink(), vec![0, 5, 9]);
+        assert_eq!((-10i32).shrink(), vec![0, -5, 10, -9]);
+        assert!(0i32.shrink().is_empty());
+    }
+
+    #[test]
+    fn passes_for_equal_implementations() {
+        let result = cross_check(
+            100,
+            |rng| {
+                (0..rng.range(0, 10))
+                    .map(|_| rng.range(-50, 50))
+                    .collect::<Vec<_>>()
+            },
+            |v| v.iter().sum::<i64>(),
+            |v| v.iter().rev().sum::<i64>(),
+        );
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn shrinks_failing_input() {
+        // `right` is wrong for lists that contain a value above 100.
+        let mismatch = cross_check(
+            100,
+            |rng| {
+                (0..rng.range(5, 20))
+                    .map(|_| rng.range(0, 1000))
+                    .collect::<Vec<_>>()
+            },
+            |v| v.iter().max().copied(),
+            |v| v.iter().filter(|x| **x <= 100).max().copied(),
+        )
+        .unwrap_err();
+
+        assert_eq!(mismatch.shrunk, vec![101]);
+        assert_eq!(mismatch.left, Outcome::Returned(Some(101)));
+        assert_eq!(mismatch.right, Outcome::Returned(None));
+    }
+
+    #[test]
+    fn treats_panics_as_mismatch() {
+        let mismatch = cross_check(
+            10,
+            |rng| rng.range(1, 10),
+            |x| 100 / x,
+            |x| if *x > 3 { panic!("too large") } else { 100 / x },
+        )
+        .unwrap_err();
+
+        assert_eq!(mismatch.shrunk, 4);
+        assert_eq!(mismatch.right, Outcome::Panicked);
+    }
+}
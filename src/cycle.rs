@@ -0,0 +1,209 @@
+//! Cycle detection for deterministic state machines, e.g. for simulations that ask about the state
+//! after a billion steps.
+//!
+//! All functions take a start state and a step function that returns the next state, or `None`
+//! if the sequence ends (e.g. when a guard leaves the map). A sequence that ends has no cycle.
+
+use std::hash::Hash;
+
+use crate::hash::{FastMap, HashMapExt};
+
+/// A cycle in a sequence of states: after `prefix` steps, the states repeat every `length`
+/// steps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    pub prefix: usize,
+    pub length: usize,
+}
+
+impl Cycle {
+    /// The first step that has the same state as step `n`, i.e. a step below
+    /// `prefix + length`.
+    pub fn equivalent_step(&self, n: usize) -> usize {
+        if n < self.prefix {
+            n
+        } else {
+            self.prefix + (n - self.prefix) % self.length
+        }
+    }
+}
+
+/// Brent's algorithm. Needs constant memory and fewer steps than [`floyd`].
+pub fn brent<S: PartialEq + Clone>(
+    start: S,
+    mut step: impl FnMut(&S) -> Option<S>,
+) -> Option<Cycle> {
+    // find the length by letting the hare run ahead of the tortoise in growing powers of two.
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = start.clone();
+    let mut hare = step(&start)?;
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare)?;
+        length += 1;
+    }
+
+    // with the hare `length` steps ahead, both meet at the start of the cycle.
+    let mut tortoise = start.clone();
+    let mut hare = start;
+    for _ in 0..length {
+        hare = step(&hare)?;
+    }
+    let mut prefix = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise)?;
+        hare = step(&hare)?;
+        prefix += 1;
+    }
+
+    Some(Cycle { prefix, length })
+}
+
+/// Floyd's tortoise and hare algorithm. Needs constant memory.
+pub fn floyd<S: PartialEq + Clone>(
+    start: S,
+    mut step: impl FnMut(&S) -> Option<S>,
+) -> Option<Cycle> {
+    let mut tortoise = step(&start)?;
+    let mut hare = step(&tortoise)?;
+    while tortoise != hare {
+        tortoise = step(&tortoise)?;
+        hare = step(&hare)?;
+        hare = step(&hare)?;
+    }
+
+    let mut prefix = 0;
+    let mut tortoise = start;
+    while tortoise != hare {
+        tortoise = step(&tortoise)?;
+        hare = step(&hare)?;
+        prefix += 1;
+    }
+
+    let mut length = 1;
+    let mut hare = step(&tortoise)?;
+    while tortoise != hare {
+        hare = step(&hare)?;
+        length += 1;
+    }
+
+    Some(Cycle { prefix, length })
+}
+
+/// Finds the cycle by remembering every state. Needs memory for `prefix + length` states, but
+/// visits every state only once, which is faster if steps are expensive.
+pub fn find_cycle<S: Hash + Eq + Clone>(
+    start: S,
+    mut step: impl FnMut(&S) -> Option<S>,
+) -> Option<Cycle> {
+    let mut seen = FastMap::new();
+    let mut state = start;
+
+    for index in 0.. {
+        if let Some(prefix) = seen.insert(state.clone(), index) {
+            return Some(Cycle {
+                prefix,
+                length: index - prefix,
+            });
+        }
+        state = step(&state)?;
+    }
+
+    unreachable!()
+}
+
+/// The state after `n` steps. Stops stepping once a cycle is found, so this takes at most
+/// `prefix + length` steps no matter how large `n` is. Returns `None` if the sequence ends
+/// before step `n`.
+pub fn nth_state<S: Hash + Eq + Clone>(
+    start: S,
+    mut step: impl FnMut(&S) -> Option<S>,
+    n: usize,
+) -> Option<S> {
+    let mut seen = FastMap::new();
+    let mut states = vec![];
+    let mut state = start;
+
+    for index in 0..n {
+        if let Some(&prefix) = seen.get(&state) {
+            let cycle = Cycle {
+                prefix,
+                length: index - prefix,
+            };
+            return Some(states.swap_remove(cycle.equivalent_step(n)));
+        }
+        seen.insert(state.clone(), index);
+        let next = step(&state)?;
+        states.push(state);
+        state = next;
+    }
+
+    Some(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{brent, find_cycle, floyd, nth_state, Cycle};
+
+    // 0, 1, ..., 4, then 5, 6, 7, 8 repeating.
+    fn step(n: &u32) -> Option<u32> {
+        Some(if *n == 8 { 5 } else { n + 1 })
+    }
+
+    const CYCLE: Cycle = Cycle {
+        prefix: 5,
+        length: 4,
+    };
+
+    #[test]
+    fn detects_cycles() {
+        assert_eq!(brent(0, step), Some(CYCLE));
+        assert_eq!(floyd(0, step), Some(CYCLE));
+        assert_eq!(find_cycle(0, step), Some(CYCLE));
+
+        // a cycle without prefix.
+        let rotate = |n: &u32| Some((n + 1) % 3);
+        let rotation = Cycle {
+            prefix: 0,
+            length: 3,
+        };
+        assert_eq!(brent(0, rotate), Some(rotation));
+        assert_eq!(floyd(0, rotate), Some(rotation));
+        assert_eq!(find_cycle(0, rotate), Some(rotation));
+
+        // a fixed point.
+        let fixed = Cycle {
+            prefix: 0,
+            length: 1,
+        };
+        assert_eq!(brent(7, |n| Some(*n)), Some(fixed));
+        assert_eq!(floyd(7, |n| Some(*n)), Some(fixed));
+    }
+
+    #[test]
+    fn finds_no_cycle_in_ending_sequences() {
+        let count_to_ten = |n: &u32| (*n < 10).then_some(n + 1);
+        assert_eq!(brent(0, count_to_ten), None);
+        assert_eq!(floyd(0, count_to_ten), None);
+        assert_eq!(find_cycle(0, count_to_ten), None);
+        assert_eq!(nth_state(0, count_to_ten, 10), Some(10));
+        assert_eq!(nth_state(0, count_to_ten, 11), None);
+    }
+
+    #[test]
+    fn jumps_to_step() {
+        assert_eq!(CYCLE.equivalent_step(3), 3);
+        assert_eq!(CYCLE.equivalent_step(9), 5);
+        assert_eq!(CYCLE.equivalent_step(1_000_000_000), 8);
+
+        assert_eq!(nth_state(0, step, 0), Some(0));
+        assert_eq!(nth_state(0, step, 7), Some(7));
+        assert_eq!(nth_state(0, step, 1_000_000_000), Some(8));
+        assert_eq!(nth_state(0, step, 1_000_000_001), Some(5));
+    }
+}
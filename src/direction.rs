@@ -0,0 +1,215 @@
+//! Directions on a grid, with `y` growing downwards.
+
+use std::str::FromStr;
+
+use crate::point::Vector;
+
+/// One of the four orthogonal directions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    /// All directions, clockwise starting at `Up`.
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    /// Turns by 90 degrees clockwise.
+    pub fn rotate_right(self) -> Self {
+        match self {
+            Direction::Up => Direction::Right,
+            Direction::Right => Direction::Down,
+            Direction::Down => Direction::Left,
+            Direction::Left => Direction::Up,
+        }
+    }
+
+    /// Turns by 90 degrees counterclockwise.
+    pub fn rotate_left(self) -> Self {
+        self.rotate_right().opposite()
+    }
+
+    pub fn opposite(self) -> Self {
+        self.rotate_right().rotate_right()
+    }
+
+    /// Returns `true` for `Up` and `Down`.
+    pub fn is_vertical(self) -> bool {
+        matches!(self, Direction::Up | Direction::Down)
+    }
+
+    /// The unit step in this direction.
+    pub fn offset(self) -> Vector {
+        let (dx, dy) = self.delta();
+        Vector::new(dx as i64, dy as i64)
+    }
+
+    /// The unit step in this direction as `(dx, dy)`, e.g. for [`crate::grid::Grid::offset`].
+    pub fn delta(self) -> (isize, isize) {
+        match self {
+            Direction::Up => (0, -1),
+            Direction::Right => (1, 0),
+            Direction::Down => (0, 1),
+            Direction::Left => (-1, 0),
+        }
+    }
+}
+
+/// Parses the arrows `^`, `>`, `v` and `<`.
+impl TryFrom<char> for Direction {
+    type Error = String;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            '^' => Ok(Direction::Up),
+            '>' => Ok(Direction::Right),
+            'v' => Ok(Direction::Down),
+            '<' => Ok(Direction::Left),
+            _ => Err(format!("unknown direction: {c}")),
+        }
+    }
+}
+
+impl FromStr for Direction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => c.try_into(),
+            _ => Err(format!("unknown direction: {s}")),
+        }
+    }
+}
+
+/// One of the four orthogonal and four diagonal directions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction8 {
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+}
+
+impl Direction8 {
+    /// All directions, clockwise starting at `Up`.
+    pub const ALL: [Direction8; 8] = [
+        Direction8::Up,
+        Direction8::UpRight,
+        Direction8::Right,
+        Direction8::DownRight,
+        Direction8::Down,
+        Direction8::DownLeft,
+        Direction8::Left,
+        Direction8::UpLeft,
+    ];
+
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    /// Turns by 45 degrees clockwise.
+    pub fn rotate_right(self) -> Self {
+        Self::ALL[(self.index() + 1) % 8]
+    }
+
+    /// Turns by 45 degrees counterclockwise.
+    pub fn rotate_left(self) -> Self {
+        Self::ALL[(self.index() + 7) % 8]
+    }
+
+    pub fn opposite(self) -> Self {
+        Self::ALL[(self.index() + 4) % 8]
+    }
+
+    /// Returns `true` for the four diagonal directions.
+    pub fn is_diagonal(self) -> bool {
+        self.index() % 2 == 1
+    }
+
+    /// The unit step in this direction.
+    pub fn offset(self) -> Vector {
+        let (dx, dy) = self.delta();
+        Vector::new(dx as i64, dy as i64)
+    }
+
+    /// The unit step in this direction as `(dx, dy)`, e.g. for [`crate::grid::Grid::offset`].
+    pub fn delta(self) -> (isize, isize) {
+        match self {
+            Direction8::Up => (0, -1),
+            Direction8::UpRight => (1, -1),
+            Direction8::Right => (1, 0),
+            Direction8::DownRight => (1, 1),
+            Direction8::Down => (0, 1),
+            Direction8::DownLeft => (-1, 1),
+            Direction8::Left => (-1, 0),
+            Direction8::UpLeft => (-1, -1),
+        }
+    }
+}
+
+impl From<Direction> for Direction8 {
+    fn from(direction: Direction) -> Self {
+        match direction {
+            Direction::Up => Direction8::Up,
+            Direction::Right => Direction8::Right,
+            Direction::Down => Direction8::Down,
+            Direction::Left => Direction8::Left,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Direction, Direction8};
+    use crate::point::Vector;
+
+    #[test]
+    fn rotates_directions() {
+        assert_eq!(Direction::Up.rotate_right(), Direction::Right);
+        assert_eq!(Direction::Up.rotate_left(), Direction::Left);
+        assert_eq!(Direction::Left.opposite(), Direction::Right);
+        assert_eq!(Direction8::Up.rotate_right(), Direction8::UpRight);
+        assert_eq!(Direction8::Up.rotate_left(), Direction8::UpLeft);
+        assert_eq!(Direction8::DownLeft.opposite(), Direction8::UpRight);
+    }
+
+    #[test]
+    fn offsets_match_rotations() {
+        for direction in Direction::ALL {
+            assert_eq!(
+                direction.rotate_right().offset(),
+                direction.offset().rotate_right()
+            );
+            assert_eq!(direction.opposite().offset(), -direction.offset());
+            assert_eq!(Direction8::from(direction).offset(), direction.offset());
+        }
+        for direction in Direction8::ALL {
+            assert_eq!(direction.opposite().offset(), -direction.offset());
+            assert_eq!(direction.offset().chebyshev(), 1);
+        }
+        assert_eq!(Direction8::DownLeft.offset(), Vector::new(-1, 1));
+    }
+
+    #[test]
+    fn parses_arrows() {
+        assert_eq!("^".parse(), Ok(Direction::Up));
+        assert_eq!(Direction::try_from('>'), Ok(Direction::Right));
+        assert_eq!(Direction::try_from('v'), Ok(Direction::Down));
+        assert_eq!("<".parse(), Ok(Direction::Left));
+        assert!("x".parse::<Direction>().is_err());
+        assert!("^^".parse::<Direction>().is_err());
+    }
+}
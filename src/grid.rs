@@ -0,0 +1,376 @@
+//! A two-dimensional grid, e.g. for maps parsed from puzzle input.
+
+use std::fmt::Display;
+use std::ops::{Index, IndexMut};
+
+use crate::parse::ParseError;
+use crate::point::Point;
+
+/// Offsets of the orthogonal neighbours: up, right, down, left.
+pub const OFFSETS_4: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+/// Offsets of the orthogonal and diagonal neighbours, clockwise starting at the top.
+pub const OFFSETS_8: [(isize, isize); 8] = [
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+    (-1, -1),
+];
+
+/// A rectangular grid stored as a flat vector in row-major order.
+///
+/// Positions are `(x, y)` tuples, with `x` being the column and `y` the row.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Creates a grid of `width` x `height` cells set to `value`.
+    pub fn new(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    /// Creates a grid from cells in row-major order.
+    ///
+    /// # Panics
+    /// If the number of cells is not a multiple of `width`.
+    pub fn from_vec(width: usize, cells: Vec<T>) -> Self {
+        assert!(
+            width > 0 && cells.len().is_multiple_of(width),
+            "{} cells do not fill rows of width {width}",
+            cells.len()
+        );
+        Self {
+            width,
+            height: cells.len() / width,
+            cells,
+        }
+    }
+
+    /// Parses a grid with one cell per character. All lines must have the same length.
+    pub fn parse(input: &str, mut cell: impl FnMut(char) -> T) -> Result<Self, ParseError> {
+        Self::try_parse(input, |c| Some(cell(c)))
+    }
+
+    /// Like [`Grid::parse`], but fails for characters that `cell` maps to `None`.
+    pub fn try_parse(
+        input: &str,
+        mut cell: impl FnMut(char) -> Option<T>,
+    ) -> Result<Self, ParseError> {
+        let mut cells = vec![];
+        let mut width = None;
+
+        for line in input.lines() {
+            // errors are reported at the remainder of the input, starting in this line.
+            let line_start = line.as_ptr() as usize - input.as_ptr() as usize;
+            let mut line_width = 0;
+            for (offset, c) in line.char_indices() {
+                let Some(value) = cell(c) else {
+                    return Err(ParseError::at(
+                        input,
+                        &input[line_start + offset..],
+                        format!("unexpected character '{c}'"),
+                    ));
+                };
+                cells.push(value);
+                line_width += 1;
+            }
+
+            match width {
+                None => width = Some(line_width),
+                Some(width) if width != line_width => {
+                    return Err(ParseError::at(
+                        input,
+                        &input[line_start..],
+                        format!("expected a row of width {width}, found {line_width}"),
+                    ));
+                }
+                Some(_) => {}
+            }
+        }
+
+        let width = width.unwrap_or_default();
+        Ok(Self {
+            width,
+            height: cells.len().checked_div(width).unwrap_or_default(),
+            cells,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns `true` if `(x, y)` lies within the grid.
+    pub fn contains(&self, (x, y): (usize, usize)) -> bool {
+        x < self.width && y < self.height
+    }
+
+    /// Returns `true` if the signed position `(x, y)` lies within the grid.
+    pub fn in_bounds(&self, x: i64, y: i64) -> bool {
+        usize::try_from(x).is_ok_and(|x| x < self.width)
+            && usize::try_from(y).is_ok_and(|y| y < self.height)
+    }
+
+    /// Index of `(x, y)` in the flat cell vector.
+    pub fn index_of(&self, (x, y): (usize, usize)) -> usize {
+        y * self.width + x
+    }
+
+    /// Position of the cell at `index` in the flat cell vector.
+    pub fn position_of(&self, index: usize) -> (usize, usize) {
+        (index % self.width, index / self.width)
+    }
+
+    pub fn get(&self, position: (usize, usize)) -> Option<&T> {
+        self.contains(position)
+            .then(|| &self.cells[self.index_of(position)])
+    }
+
+    /// Like [`Grid::get`], for signed points.
+    pub fn get_point(&self, point: Point) -> Option<&T> {
+        self.get(point.to_position()?)
+    }
+
+    pub fn get_mut(&mut self, position: (usize, usize)) -> Option<&mut T> {
+        if self.contains(position) {
+            let index = self.index_of(position);
+            Some(&mut self.cells[index])
+        } else {
+            None
+        }
+    }
+
+    /// Moves `position` by `(dx, dy)`. Returns `None` if the result lies outside of the grid.
+    pub fn offset(
+        &self,
+        (x, y): (usize, usize),
+        (dx, dy): (isize, isize),
+    ) -> Option<(usize, usize)> {
+        let position = (x.checked_add_signed(dx)?, y.checked_add_signed(dy)?);
+        self.contains(position).then_some(position)
+    }
+
+    /// Orthogonal neighbours of `position` that lie within the grid.
+    pub fn neighbours_4(
+        &self,
+        position: (usize, usize),
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        OFFSETS_4
+            .into_iter()
+            .filter_map(move |offset| self.offset(position, offset))
+    }
+
+    /// Orthogonal and diagonal neighbours of `position` that lie within the grid.
+    pub fn neighbours_8(
+        &self,
+        position: (usize, usize),
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        OFFSETS_8
+            .into_iter()
+            .filter_map(move |offset| self.offset(position, offset))
+    }
+
+    /// All positions in row-major order.
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.width;
+        (0..self.cells.len()).map(move |i| (i % width, i / width))
+    }
+
+    /// All cells with their positions in row-major order.
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    /// All cells with their positions in row-major order, mutably.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = ((usize, usize), &mut T)> {
+        self.positions().zip(self.cells.iter_mut())
+    }
+
+    /// All cells in row-major order.
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    /// All cells in row-major order, mutably.
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.cells.iter_mut()
+    }
+
+    /// The rows of the grid from top to bottom.
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    /// Position of the first cell in row-major order that matches `predicate`.
+    pub fn find(&self, predicate: impl FnMut(&T) -> bool) -> Option<(usize, usize)> {
+        self.cells
+            .iter()
+            .position(predicate)
+            .map(|index| self.position_of(index))
+    }
+
+    /// Creates a grid of the same size by mapping every cell.
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, position: (usize, usize)) -> &Self::Output {
+        self.get(position).unwrap_or_else(|| {
+            panic!(
+                "position {position:?} out of bounds of {}x{} grid",
+                self.width, self.height
+            )
+        })
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, position: (usize, usize)) -> &mut Self::Output {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(position).unwrap_or_else(|| {
+            panic!("position {position:?} out of bounds of {width}x{height} grid")
+        })
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point) -> &Self::Output {
+        match point.to_position() {
+            Some(position) => &self[position],
+            None => panic!("point {point:?} out of bounds of grid"),
+        }
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, point: Point) -> &mut Self::Output {
+        match point.to_position() {
+            Some(position) => &mut self[position],
+            None => panic!("point {point:?} out of bounds of grid"),
+        }
+    }
+}
+
+/// Writes one line per row, each cell formatted with its `Display` implementation.
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Grid;
+    use crate::point::Point;
+
+    const INPUT: &str = "ab.\n.#c\n";
+
+    #[test]
+    fn parses_characters() {
+        let grid = Grid::parse(INPUT, |c| c).unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(1, 0)], 'b');
+        assert_eq!(grid[(2, 1)], 'c');
+        assert_eq!(grid.get((3, 0)), None);
+        assert_eq!(grid.get((0, 2)), None);
+    }
+
+    #[test]
+    fn reports_invalid_input() {
+        let err = Grid::try_parse("..\n.x\n", |c| (c == '.').then_some(())).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 2));
+
+        let err = Grid::parse("...\n..\n", |c| c).unwrap_err();
+        assert_eq!(err.line, 2);
+        assert_eq!(err.message, "expected a row of width 3, found 2");
+    }
+
+    #[test]
+    fn lists_neighbours_within_bounds() {
+        let grid = Grid::new(3, 3, 0);
+        assert_eq!(
+            grid.neighbours_4((0, 0)).collect::<Vec<_>>(),
+            vec![(1, 0), (0, 1)]
+        );
+        assert_eq!(grid.neighbours_4((1, 1)).count(), 4);
+        assert_eq!(grid.neighbours_8((1, 1)).count(), 8);
+        assert_eq!(grid.neighbours_8((2, 2)).count(), 3);
+    }
+
+    #[test]
+    fn finds_and_enumerates_cells() {
+        let grid = Grid::parse(INPUT, |c| c).unwrap();
+        assert_eq!(grid.find(|c| *c == '#'), Some((1, 1)));
+        assert_eq!(grid.find(|c| *c == 'x'), None);
+        assert_eq!(
+            grid.iter()
+                .filter(|(_, c)| c.is_alphabetic())
+                .map(|(position, _)| position)
+                .collect::<Vec<_>>(),
+            vec![(0, 0), (1, 0), (2, 1)]
+        );
+    }
+
+    #[test]
+    fn mutates_cells() {
+        let mut grid = Grid::parse(INPUT, |c| c).unwrap();
+        *grid.get_mut((0, 1)).unwrap() = 'd';
+        grid[(2, 0)] = 'e';
+        assert_eq!(grid.get_mut((5, 5)), None);
+        assert_eq!(grid.to_string(), "abe\nd#c\n");
+    }
+
+    #[test]
+    fn indexes_with_points() {
+        let mut grid = Grid::parse(INPUT, |c| c).unwrap();
+        grid[Point::new(0, 1)] = 'd';
+        assert_eq!(grid[Point::new(0, 1)], 'd');
+        assert_eq!(grid.get_point(Point::new(2, 1)), Some(&'c'));
+        assert_eq!(grid.get_point(Point::new(-1, 1)), None);
+    }
+
+    #[test]
+    fn checks_signed_bounds() {
+        let grid = Grid::new(2, 3, ());
+        assert!(grid.in_bounds(1, 2));
+        assert!(!grid.in_bounds(-1, 0));
+        assert!(!grid.in_bounds(2, 0));
+        assert_eq!(grid.offset((0, 0), (-1, 0)), None);
+        assert_eq!(grid.offset((0, 0), (1, 2)), Some((1, 2)));
+    }
+}
@@ -0,0 +1,150 @@
+//! A fast, non-cryptographic hasher for hash maps and sets in solutions.
+//!
+//! The default SipHash of `std` protects against hash flooding, which puzzle inputs don't need.
+//! [`FastHasher`] uses the multiply-rotate scheme of the Fx hasher from rustc and is much faster
+//! for small keys like integers, points and short strings.
+//!
+//! To switch a solution, import the aliases and the extension traits, e.g.
+//! `use advent_of_code::hash::{FastMap as HashMap, HashMapExt};`.
+
+use std::collections::{HashMap, HashSet};
+use std::hash::{BuildHasher, Hasher};
+
+/// A `HashMap` using [`FastHasher`].
+pub type FastMap<K, V> = HashMap<K, V, FastBuildHasher>;
+
+/// A `HashSet` using [`FastHasher`].
+pub type FastSet<T> = HashSet<T, FastBuildHasher>;
+
+const SEED: u64 = 0x51_7c_c1_b7_27_22_0a_95;
+
+/// The Fx hash function. Not resistant against collisions chosen by an attacker.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FastHasher {
+    hash: u64,
+}
+
+impl FastHasher {
+    fn add(&mut self, word: u64) {
+        self.hash = (self.hash.rotate_left(5) ^ word).wrapping_mul(SEED);
+    }
+}
+
+impl Hasher for FastHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        let mut chunks = bytes.chunks_exact(8);
+        for chunk in &mut chunks {
+            self.add(u64::from_le_bytes(chunk.try_into().unwrap()));
+        }
+        let rest = chunks.remainder();
+        if !rest.is_empty() {
+            let mut word = [0; 8];
+            word[..rest.len()].copy_from_slice(rest);
+            self.add(u64::from_le_bytes(word));
+        }
+    }
+
+    fn write_u8(&mut self, i: u8) {
+        self.add(i.into());
+    }
+
+    fn write_u16(&mut self, i: u16) {
+        self.add(i.into());
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.add(i.into());
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.add(i);
+    }
+
+    fn write_u128(&mut self, i: u128) {
+        self.add(i as u64);
+        self.add((i >> 64) as u64);
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.add(i as u64);
+    }
+
+    fn finish(&self) -> u64 {
+        self.hash
+    }
+}
+
+/// Creates [`FastHasher`]s, for use as the hasher of `HashMap` and `HashSet`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FastBuildHasher;
+
+impl BuildHasher for FastBuildHasher {
+    type Hasher = FastHasher;
+
+    fn build_hasher(&self) -> Self::Hasher {
+        FastHasher::default()
+    }
+}
+
+/// `new()` and `with_capacity()` for [`FastMap`], which `std` only provides for the default hasher.
+pub trait HashMapExt {
+    fn new() -> Self;
+    fn with_capacity(capacity: usize) -> Self;
+}
+
+impl<K, V> HashMapExt for FastMap<K, V> {
+    fn new() -> Self {
+        HashMap::with_hasher(FastBuildHasher)
+    }
+
+    fn with_capacity(capacity: usize) -> Self {
+        HashMap::with_capacity_and_hasher(capacity, FastBuildHasher)
+    }
+}
+
+/// `new()` and `with_capacity()` for [`FastSet`], which `std` only provides for the default hasher.
+pub trait HashSetExt {
+    fn new() -> Self;
+    fn with_capacity(capacity: usize) -> Self;
+}
+
+impl<T> HashSetExt for FastSet<T> {
+    fn new() -> Self {
+        HashSet::with_hasher(FastBuildHasher)
+    }
+
+    fn with_capacity(capacity: usize) -> Self {
+        HashSet::with_capacity_and_hasher(capacity, FastBuildHasher)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::hash::BuildHasher;
+
+    use super::{FastBuildHasher, FastMap, FastSet, HashMapExt, HashSetExt};
+
+    #[test]
+    fn hashes_deterministically() {
+        let hasher = FastBuildHasher;
+        assert_eq!(hasher.hash_one((1, 2)), hasher.hash_one((1, 2)));
+        assert_ne!(hasher.hash_one((1, 2)), hasher.hash_one((2, 1)));
+        assert_ne!(hasher.hash_one("ab"), hasher.hash_one("abc"));
+        assert_ne!(
+            hasher.hash_one("a long string of 17"),
+            hasher.hash_one("a long string of 18")
+        );
+    }
+
+    #[test]
+    fn works_in_collections() {
+        let mut map = FastMap::new();
+        map.insert("a", 1);
+        *map.entry("a").or_default() += 1;
+        assert_eq!(map.get("a"), Some(&2));
+
+        let set: FastSet<_> = (0..1000).map(|i| i % 10).collect();
+        assert_eq!(set.len(), 10);
+        assert!(FastSet::<u8>::with_capacity(16).is_empty());
+    }
+}
@@ -0,0 +1,226 @@
+//! Image export of grids without external crates, e.g. to look at the states of a simulation.
+//!
+//! [`Image::from_grid`] maps every cell to a color. Images can be written as binary PPM or as PNG
+//! with uncompressed ("stored") deflate blocks, which every image viewer can open.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::grid::Grid;
+
+/// A color with 8 bits per channel.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const BLACK: Rgb = Rgb(0, 0, 0);
+    pub const WHITE: Rgb = Rgb(255, 255, 255);
+}
+
+/// An RGB image.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    pixels: Grid<Rgb>,
+}
+
+impl Image {
+    /// Creates an image with one pixel per cell, colored by `color`.
+    pub fn from_grid<T>(grid: &Grid<T>, color: impl FnMut(&T) -> Rgb) -> Self {
+        Self {
+            pixels: grid.map(color),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.pixels.width()
+    }
+
+    pub fn height(&self) -> usize {
+        self.pixels.height()
+    }
+
+    /// Scales the image up, so each pixel becomes a `factor` x `factor` square. A `factor` of `0`
+    /// gives an empty image.
+    pub fn scaled(&self, factor: usize) -> Self {
+        let (width, height) = (self.width() * factor, self.height() * factor);
+        let mut pixels = Grid::new(width, height, Rgb::BLACK);
+        for ((x, y), pixel) in pixels.iter_mut() {
+            *pixel = self.pixels[(x / factor, y / factor)];
+        }
+        Self { pixels }
+    }
+
+    fn rgb_bytes(&self) -> impl Iterator<Item = u8> + '_ {
+        self.pixels
+            .values()
+            .flat_map(|&Rgb(red, green, blue)| [red, green, blue])
+    }
+
+    /// Encodes the image as binary PPM (`P6`).
+    pub fn to_ppm(&self) -> Vec<u8> {
+        let mut bytes = format!("P6\n{} {}\n255\n", self.width(), self.height()).into_bytes();
+        bytes.extend(self.rgb_bytes());
+        bytes
+    }
+
+    /// Encodes the image as an 8-bit RGB PNG. The image data is not compressed.
+    pub fn to_png(&self) -> Vec<u8> {
+        let mut header = vec![];
+        header.extend((self.width() as u32).to_be_bytes());
+        header.extend((self.height() as u32).to_be_bytes());
+        // bit depth 8, color type RGB, default compression, filter and no interlacing.
+        header.extend([8, 2, 0, 0, 0]);
+
+        // every row starts with its filter type, 0 for none.
+        let mut rows = Vec::with_capacity((self.width() * 3 + 1) * self.height());
+        for row in self.pixels.rows() {
+            rows.push(0);
+            rows.extend(
+                row.iter()
+                    .flat_map(|&Rgb(red, green, blue)| [red, green, blue]),
+            );
+        }
+
+        let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+        write_chunk(&mut png, b"IHDR", &header);
+        write_chunk(&mut png, b"IDAT", &zlib_stored(&rows));
+        write_chunk(&mut png, b"IEND", &[]);
+        png
+    }
+
+    /// Writes the image to `path`, as PPM if the extension is `ppm` and as PNG otherwise.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        let bytes = match path.extension().and_then(|extension| extension.to_str()) {
+            Some("ppm") => self.to_ppm(),
+            _ => self.to_png(),
+        };
+        fs::write(path, bytes)
+    }
+}
+
+fn write_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend((data.len() as u32).to_be_bytes());
+    let start = png.len();
+    png.extend(kind);
+    png.extend(data);
+    let crc = crc32(&png[start..]);
+    png.extend(crc.to_be_bytes());
+}
+
+/// Wraps `data` in a zlib stream of stored deflate blocks.
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    const MAX_BLOCK: usize = u16::MAX as usize;
+
+    // deflate with a 32K window, no preset dictionary and the fastest compression level.
+    let mut stream = vec![0x78, 0x01];
+    let mut blocks = data.chunks(MAX_BLOCK).peekable();
+    if blocks.peek().is_none() {
+        stream.extend([1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(block) = blocks.next() {
+        let is_final = blocks.peek().is_none();
+        let len = block.len() as u16;
+        stream.push(u8::from(is_final));
+        stream.extend(len.to_le_bytes());
+        stream.extend((!len).to_le_bytes());
+        stream.extend(block);
+    }
+    stream.extend(adler32(data).to_be_bytes());
+    stream
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    const MOD: u32 = 65521;
+    let (mut a, mut b) = (1, 0);
+    // the sums fit into a u32 for chunks of up to 5552 bytes before they have to be reduced.
+    for chunk in data.chunks(5552) {
+        for &byte in chunk {
+            a += u32::from(byte);
+            b += a;
+        }
+        a %= MOD;
+        b %= MOD;
+    }
+    (b << 16) | a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{adler32, crc32, zlib_stored, Image, Rgb};
+    use crate::grid::Grid;
+
+    fn image() -> Image {
+        let grid = Grid::parse("#.\n.#\n.#\n", |c| c == '#').unwrap();
+        Image::from_grid(&grid, |&set| if set { Rgb::WHITE } else { Rgb(255, 0, 0) })
+    }
+
+    #[test]
+    fn computes_checksums() {
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+        assert_eq!(crc32(b"IEND"), 0xae42_6082);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+        assert_eq!(adler32(&vec![255; 100_000]), 0x149a_302c);
+    }
+
+    #[test]
+    fn writes_ppm() {
+        let ppm = image().to_ppm();
+        assert!(ppm.starts_with(b"P6\n2 3\n255\n"));
+        assert_eq!(ppm.len(), 11 + 2 * 3 * 3);
+        assert_eq!(&ppm[11..17], &[255, 255, 255, 255, 0, 0]);
+    }
+
+    #[test]
+    fn writes_png() {
+        let png = image().to_png();
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR\0\0\0\x02\0\0\0\x03\x08\x02"));
+        assert!(png.ends_with(b"\0\0\0\0IEND\xae\x42\x60\x82"));
+    }
+
+    #[test]
+    fn splits_stored_blocks() {
+        let data = vec![7; 70_000];
+        let stream = zlib_stored(&data);
+        // header, two blocks with 5 bytes of header each and the checksum.
+        assert_eq!(stream.len(), 2 + 5 + 65535 + 5 + 4465 + 4);
+        assert_eq!(&stream[2..7], &[0, 0xff, 0xff, 0, 0]);
+        assert_eq!(&stream[65542..65547], &[1, 0x71, 0x11, 0x8e, 0xee]);
+
+        assert_eq!(
+            zlib_stored(&[]),
+            vec![0x78, 0x01, 1, 0, 0, 0xff, 0xff, 0, 0, 0, 1]
+        );
+    }
+
+    #[test]
+    fn scales_images() {
+        let scaled = image().scaled(2);
+        assert_eq!((scaled.width(), scaled.height()), (4, 6));
+        assert_eq!(
+            &scaled.to_ppm()[11..23],
+            &[255, 255, 255, 255, 255, 255, 255, 0, 0, 255, 0, 0]
+        );
+
+        let empty = image().scaled(0);
+        assert_eq!((empty.width(), empty.height()), (0, 0));
+        assert!(empty.to_png().starts_with(b"\x89PNG"));
+        assert_eq!(empty.scaled(3), empty);
+    }
+}
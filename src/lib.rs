@@ -1,3 +1,20 @@
+pub mod bitset;
+pub mod counter;
+pub mod cross_check;
+pub mod cycle;
+pub mod direction;
+pub mod grid;
+pub mod hash;
+pub mod image;
+pub mod linear;
+pub mod math;
+pub mod order;
+pub mod parallel;
+pub mod parse;
+pub mod point;
+pub mod push;
+pub mod region;
+pub mod search;
 pub mod template;
 
 // Use this file to add helper functions and additional modules.
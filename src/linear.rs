@@ -0,0 +1,517 @@
+//! Exact solver for small systems of linear equations with integer coefficients, e.g. for puzzles
+//! about button presses that have to add up to a target.
+//!
+//! All arithmetic is done on [`Rational`]s over `i128`, so there are no rounding errors.
+
+use std::cmp::Ordering;
+use std::fmt::Display;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use crate::math::{crt, mod_inverse};
+
+/// A fraction in lowest terms with a positive denominator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rational {
+    numerator: i128,
+    denominator: i128,
+}
+
+impl Rational {
+    pub const ZERO: Rational = Rational::integer(0);
+    pub const ONE: Rational = Rational::integer(1);
+
+    /// Creates the fraction `numerator / denominator`.
+    ///
+    /// # Panics
+    /// If `denominator` is zero.
+    pub fn new(numerator: i128, denominator: i128) -> Self {
+        assert!(denominator != 0, "denominator must not be zero");
+        let divisor = gcd(numerator, denominator) * denominator.signum();
+        Self {
+            numerator: numerator / divisor,
+            denominator: denominator / divisor,
+        }
+    }
+
+    pub const fn integer(value: i128) -> Self {
+        Self {
+            numerator: value,
+            denominator: 1,
+        }
+    }
+
+    pub fn numerator(self) -> i128 {
+        self.numerator
+    }
+
+    pub fn denominator(self) -> i128 {
+        self.denominator
+    }
+
+    pub fn is_integer(self) -> bool {
+        self.denominator == 1
+    }
+
+    /// The value as an integer, or `None` if it is a proper fraction.
+    pub fn to_integer(self) -> Option<i128> {
+        self.is_integer().then_some(self.numerator)
+    }
+
+    /// The largest integer less than or equal to the value.
+    pub fn floor(self) -> i128 {
+        self.numerator.div_euclid(self.denominator)
+    }
+
+    /// The smallest integer greater than or equal to the value.
+    pub fn ceil(self) -> i128 {
+        -(-self).floor()
+    }
+}
+
+impl From<i128> for Rational {
+    fn from(value: i128) -> Self {
+        Rational::integer(value)
+    }
+}
+
+impl Add for Rational {
+    type Output = Rational;
+
+    fn add(self, rhs: Rational) -> Self::Output {
+        Rational::new(
+            self.numerator * rhs.denominator + rhs.numerator * self.denominator,
+            self.denominator * rhs.denominator,
+        )
+    }
+}
+
+impl Sub for Rational {
+    type Output = Rational;
+
+    fn sub(self, rhs: Rational) -> Self::Output {
+        self + -rhs
+    }
+}
+
+impl Mul for Rational {
+    type Output = Rational;
+
+    fn mul(self, rhs: Rational) -> Self::Output {
+        Rational::new(
+            self.numerator * rhs.numerator,
+            self.denominator * rhs.denominator,
+        )
+    }
+}
+
+impl Div for Rational {
+    type Output = Rational;
+
+    /// # Panics
+    /// If `rhs` is zero.
+    fn div(self, rhs: Rational) -> Self::Output {
+        Rational::new(
+            self.numerator * rhs.denominator,
+            self.denominator * rhs.numerator,
+        )
+    }
+}
+
+impl Neg for Rational {
+    type Output = Rational;
+
+    fn neg(self) -> Self::Output {
+        Rational {
+            numerator: -self.numerator,
+            denominator: self.denominator,
+        }
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // denominators are positive, so cross-multiplying keeps the order.
+        (self.numerator * other.denominator).cmp(&(other.numerator * self.denominator))
+    }
+}
+
+impl Display for Rational {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_integer() {
+            write!(f, "{}", self.numerator)
+        } else {
+            write!(f, "{}/{}", self.numerator, self.denominator)
+        }
+    }
+}
+
+fn gcd(a: i128, b: i128) -> i128 {
+    let (mut a, mut b) = (a.abs(), b.abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a.max(1)
+}
+
+/// The solutions of a linear system, see [`System::solve`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Solution {
+    /// Exactly one solution.
+    Unique(Vec<Rational>),
+    /// The equations contradict each other.
+    Inconsistent,
+    /// Infinitely many solutions.
+    Infinite(Affine),
+}
+
+/// The solutions `particular + t_1 * directions[0] + t_2 * directions[1] + ...` for all
+/// rational `t_i`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Affine {
+    pub particular: Vec<Rational>,
+    pub directions: Vec<Vec<Rational>>,
+}
+
+/// A system of linear equations `coefficients * x = constants` with integer coefficients.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct System {
+    coefficients: Vec<Vec<i128>>,
+    constants: Vec<i128>,
+    unknowns: usize,
+}
+
+impl System {
+    /// Creates a system with one equation per row of `coefficients`.
+    ///
+    /// # Panics
+    /// If the rows have different lengths or there is not one constant per row.
+    pub fn new(coefficients: Vec<Vec<i128>>, constants: Vec<i128>) -> Self {
+        assert_eq!(
+            coefficients.len(),
+            constants.len(),
+            "expected one constant per equation"
+        );
+        let unknowns = coefficients.first().map_or(0, Vec::len);
+        assert!(
+            coefficients.iter().all(|row| row.len() == unknowns),
+            "all equations must have {unknowns} coefficients"
+        );
+        Self {
+            coefficients,
+            constants,
+            unknowns,
+        }
+    }
+
+    /// Solves the system with Gauss-Jordan elimination.
+    pub fn solve(&self) -> Solution {
+        let mut rows: Vec<Vec<Rational>> = self
+            .coefficients
+            .iter()
+            .zip(&self.constants)
+            .map(|(row, &constant)| {
+                row.iter()
+                    .chain([&constant])
+                    .map(|&value| Rational::integer(value))
+                    .collect()
+            })
+            .collect();
+
+        let mut pivots = vec![];
+        for column in 0..self.unknowns {
+            let rank = pivots.len();
+            let Some(pivot) = (rank..rows.len()).find(|&row| rows[row][column] != Rational::ZERO)
+            else {
+                continue;
+            };
+            rows.swap(rank, pivot);
+
+            let factor = rows[rank][column];
+            for value in rows[rank].iter_mut() {
+                *value = *value / factor;
+            }
+
+            let pivot_row = rows[rank].clone();
+            for (row, values) in rows.iter_mut().enumerate() {
+                let factor = values[column];
+                if row == rank || factor == Rational::ZERO {
+                    continue;
+                }
+                for (value, &pivot_value) in values.iter_mut().zip(&pivot_row) {
+                    *value = *value - factor * pivot_value;
+                }
+            }
+
+            pivots.push(column);
+        }
+
+        // a remaining row reads 0 = c.
+        if rows[pivots.len()..]
+            .iter()
+            .any(|row| row[self.unknowns] != Rational::ZERO)
+        {
+            return Solution::Inconsistent;
+        }
+
+        let mut particular = vec![Rational::ZERO; self.unknowns];
+        for (row, &column) in pivots.iter().enumerate() {
+            particular[column] = rows[row][self.unknowns];
+        }
+
+        if pivots.len() == self.unknowns {
+            return Solution::Unique(particular);
+        }
+
+        let directions = (0..self.unknowns)
+            .filter(|column| !pivots.contains(column))
+            .map(|free| {
+                let mut direction = vec![Rational::ZERO; self.unknowns];
+                direction[free] = Rational::ONE;
+                for (row, &column) in pivots.iter().enumerate() {
+                    direction[column] = -rows[row][free];
+                }
+                direction
+            })
+            .collect();
+
+        Solution::Infinite(Affine {
+            particular,
+            directions,
+        })
+    }
+
+    /// Finds the non-negative integer solution with the smallest total `cost`, i.e. the sum of
+    /// `cost[i] * x[i]`. Returns the cost and the solution.
+    ///
+    /// If the system has infinitely many solutions, every free parameter is limited to
+    /// `0..=limit`. All but the last free parameter are enumerated, the last one is solved
+    /// directly, so systems with a single degree of freedom are cheap even for a large `limit`.
+    pub fn min_cost_non_negative(&self, cost: &[i128], limit: i128) -> Option<(i128, Vec<i128>)> {
+        assert_eq!(cost.len(), self.unknowns, "expected one cost per unknown");
+
+        match self.solve() {
+            Solution::Inconsistent => None,
+            Solution::Unique(values) => {
+                let values = values
+                    .into_iter()
+                    .map(|value| value.to_integer().filter(|value| *value >= 0))
+                    .collect::<Option<Vec<_>>>()?;
+                Some((total_cost(cost, &values), values))
+            }
+            Solution::Infinite(affine) => {
+                min_cost_search(&affine.particular, &affine.directions, cost, limit)
+            }
+        }
+    }
+}
+
+fn total_cost(cost: &[i128], values: &[i128]) -> i128 {
+    cost.iter().zip(values).map(|(c, x)| c * x).sum()
+}
+
+fn min_cost_search(
+    point: &[Rational],
+    directions: &[Vec<Rational>],
+    cost: &[i128],
+    limit: i128,
+) -> Option<(i128, Vec<i128>)> {
+    match directions {
+        [] => {
+            let values = point
+                .iter()
+                .map(|value| value.to_integer().filter(|value| *value >= 0))
+                .collect::<Option<Vec<_>>>()?;
+            Some((total_cost(cost, &values), values))
+        }
+        [direction] => {
+            let t = best_parameter(point, direction, cost, limit)?;
+            let point = step(point, direction, t);
+            min_cost_search(&point, &[], cost, limit)
+        }
+        [direction, rest @ ..] => (0..=limit)
+            .filter_map(|t| min_cost_search(&step(point, direction, t), rest, cost, limit))
+            .min(),
+    }
+}
+
+fn step(point: &[Rational], direction: &[Rational], t: i128) -> Vec<Rational> {
+    point
+        .iter()
+        .zip(direction)
+        .map(|(&p, &d)| p + d * Rational::integer(t))
+        .collect()
+}
+
+/// The `t` in `0..=limit` that makes `point + t * direction` a non-negative integer vector with
+/// the smallest cost.
+fn best_parameter(
+    point: &[Rational],
+    direction: &[Rational],
+    cost: &[i128],
+    limit: i128,
+) -> Option<i128> {
+    let (mut low, mut high) = (0, limit);
+    let mut congruences = vec![];
+
+    for (&p, &d) in point.iter().zip(direction) {
+        // p + d * t >= 0
+        match d.cmp(&Rational::ZERO) {
+            Ordering::Greater => low = low.max((-p / d).ceil()),
+            Ordering::Less => high = high.min((p / -d).floor()),
+            Ordering::Equal if p < Rational::ZERO => return None,
+            Ordering::Equal => {}
+        }
+
+        // p + d * t is an integer: with both scaled to the common denominator m, this is
+        // a * t ≡ b (mod m).
+        let m = p.denominator() / gcd(p.denominator(), d.denominator()) * d.denominator();
+        let a = (d.numerator() * (m / d.denominator())).rem_euclid(m);
+        let b = (-p.numerator() * (m / p.denominator())).rem_euclid(m);
+        let g = gcd(a, m);
+        if b % g != 0 {
+            return None;
+        }
+        let (a, b, m) = (a / g, b / g, m / g);
+        if m > 1 {
+            let (b, m) = (to_i64(b), to_i64(m));
+            let inverse = mod_inverse(to_i64(a), m).expect("a and m are coprime");
+            let residue = (i128::from(b) * i128::from(inverse)).rem_euclid(m.into());
+            congruences.push((residue as i64, m));
+        }
+    }
+
+    let (residue, modulus) = crt(&congruences)?;
+    let (residue, modulus) = (i128::from(residue), i128::from(modulus));
+
+    let slope: i128 = direction
+        .iter()
+        .zip(cost)
+        .map(|(&d, &c)| d * Rational::integer(c))
+        .fold(Rational::ZERO, |sum, value| sum + value)
+        .numerator();
+
+    let t = if slope >= 0 {
+        low + (residue - low).rem_euclid(modulus)
+    } else {
+        high - (high - residue).rem_euclid(modulus)
+    };
+    (low..=high).contains(&t).then_some(t)
+}
+
+fn to_i64(value: i128) -> i64 {
+    i64::try_from(value).expect("denominators of the solution do not fit into i64")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Affine, Rational, Solution, System};
+
+    fn rationals(values: &[(i128, i128)]) -> Vec<Rational> {
+        values.iter().map(|&(n, d)| Rational::new(n, d)).collect()
+    }
+
+    #[test]
+    fn does_rational_arithmetic() {
+        let half = Rational::new(2, 4);
+        assert_eq!((half.numerator(), half.denominator()), (1, 2));
+        assert_eq!(Rational::new(3, -6), -half);
+        assert_eq!(half + Rational::new(1, 3), Rational::new(5, 6));
+        assert_eq!(half - Rational::ONE, Rational::new(-1, 2));
+        assert_eq!(half * Rational::integer(4), Rational::integer(2));
+        assert_eq!(half / Rational::new(1, 4), Rational::integer(2));
+        assert!(Rational::new(-1, 2) < Rational::new(1, 3));
+        assert_eq!(
+            (Rational::new(-3, 2).floor(), Rational::new(-3, 2).ceil()),
+            (-2, -1)
+        );
+        assert_eq!(Rational::new(7, 2).to_string(), "7/2");
+    }
+
+    #[test]
+    fn solves_unique_systems() {
+        // 94a + 22b = 8400, 34a + 67b = 5400
+        let system = System::new(vec![vec![94, 22], vec![34, 67]], vec![8400, 5400]);
+        assert_eq!(
+            system.solve(),
+            Solution::Unique(rationals(&[(80, 1), (40, 1)]))
+        );
+        assert_eq!(
+            system.min_cost_non_negative(&[3, 1], 100),
+            Some((280, vec![80, 40]))
+        );
+
+        let system = System::new(vec![vec![2, 0], vec![0, 1]], vec![1, 1]);
+        assert_eq!(
+            system.solve(),
+            Solution::Unique(rationals(&[(1, 2), (1, 1)]))
+        );
+        assert_eq!(system.min_cost_non_negative(&[1, 1], 100), None);
+    }
+
+    #[test]
+    fn detects_inconsistent_systems() {
+        let system = System::new(vec![vec![1, 2], vec![2, 4]], vec![3, 7]);
+        assert_eq!(system.solve(), Solution::Inconsistent);
+        assert_eq!(system.min_cost_non_negative(&[1, 1], 100), None);
+    }
+
+    #[test]
+    fn describes_infinite_solutions() {
+        let system = System::new(vec![vec![1, 2], vec![2, 4]], vec![3, 6]);
+        assert_eq!(
+            system.solve(),
+            Solution::Infinite(Affine {
+                particular: rationals(&[(3, 1), (0, 1)]),
+                directions: vec![rationals(&[(-2, 1), (1, 1)])],
+            })
+        );
+    }
+
+    #[test]
+    fn finds_cheapest_solution_of_degenerate_systems() {
+        // parallel buttons: 2a + 4b = 20 (twice). b is cheaper per step.
+        let system = System::new(vec![vec![2, 4], vec![2, 4]], vec![20, 20]);
+        assert_eq!(
+            system.min_cost_non_negative(&[3, 1], 100),
+            Some((5, vec![0, 5]))
+        );
+        assert_eq!(
+            system.min_cost_non_negative(&[1, 3], 100),
+            Some((10, vec![10, 0]))
+        );
+
+        // 4a + 6b = 14 only has a = 2, b = 1 as non-negative integer solution.
+        let system = System::new(vec![vec![4, 6]], vec![14]);
+        assert_eq!(
+            system.min_cost_non_negative(&[1, 1], 100),
+            Some((3, vec![2, 1]))
+        );
+
+        // 4a + 6b = 15 has no integer solutions at all.
+        let system = System::new(vec![vec![4, 6]], vec![15]);
+        assert_eq!(system.min_cost_non_negative(&[1, 1], 100), None);
+
+        // the limit also applies to a free parameter that is not bounded by the system.
+        let system = System::new(vec![vec![1, -1]], vec![0]);
+        assert_eq!(
+            system.min_cost_non_negative(&[-1, 0], 7),
+            Some((-7, vec![7, 7]))
+        );
+    }
+
+    #[test]
+    fn searches_several_free_parameters() {
+        // a + b + c = 10 with a cost that prefers c.
+        let system = System::new(vec![vec![1, 1, 1]], vec![10]);
+        assert_eq!(
+            system.min_cost_non_negative(&[3, 2, 1], 10),
+            Some((10, vec![0, 0, 10]))
+        );
+    }
+}
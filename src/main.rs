@@ -7,8 +7,11 @@ use advent_of_code::template::Day;
 use std::process;
 
 mod args {
-    use advent_of_code::template::Day;
+    use advent_of_code::template::commands::solve::DebugOptions;
+    use advent_of_code::template::runner::BenchOptions;
+    use advent_of_code::template::{Day, InputSource};
     use std::process;
+    use std::time::Duration;
 
     pub enum AppArguments {
         Download {
@@ -27,14 +30,24 @@ mod args {
             release: bool,
             dhat: bool,
             submit: Option<u8>,
+            input: InputSource,
+            bench: Option<BenchOptions>,
+            debug: DebugOptions,
         },
         All {
             release: bool,
+            timeout: Option<Duration>,
         },
         Time {
             all: bool,
             day: Option<Day>,
             store: bool,
+            bench: BenchOptions,
+            timeout: Option<Duration>,
+        },
+        Variants {
+            day: Day,
+            bench: BenchOptions,
         },
         #[cfg(feature = "today")]
         Today,
@@ -46,15 +59,34 @@ mod args {
         let app_args = match args.subcommand()?.as_deref() {
             Some("all") => AppArguments::All {
                 release: args.contains("--release"),
+                timeout: parse_timeout(&mut args)?,
             },
             Some("time") => {
                 let all = args.contains("--all");
                 let store = args.contains("--store");
+                let variants = args.contains("--variants");
+                let bench = parse_bench_options(&mut args);
+                let timeout = parse_timeout(&mut args)?;
 
-                AppArguments::Time {
-                    all,
-                    day: args.opt_free_from_str()?,
-                    store,
+                if variants {
+                    if all || store || timeout.is_some() {
+                        return Err(
+                            "`--all`, `--store` and `--timeout` can not be combined with `--variants`."
+                                .into(),
+                        );
+                    }
+                    AppArguments::Variants {
+                        day: args.free_from_str()?,
+                        bench,
+                    }
+                } else {
+                    AppArguments::Time {
+                        all,
+                        day: args.opt_free_from_str()?,
+                        store,
+                        bench,
+                        timeout,
+                    }
                 }
             }
             Some("download") => AppArguments::Download {
@@ -68,12 +100,47 @@ mod args {
                 download: args.contains("--download"),
                 overwrite: args.contains("--overwrite"),
             },
-            Some("solve") => AppArguments::Solve {
-                day: args.free_from_str()?,
-                release: args.contains("--release"),
-                submit: args.opt_value_from_str("--submit")?,
-                dhat: args.contains("--dhat"),
-            },
+            Some("solve") => {
+                let release = args.contains("--release");
+                let dhat = args.contains("--dhat");
+                let time = args.contains("--time");
+                let bench = parse_bench_options(&mut args);
+                let mut verbosity = 0;
+                while args.contains(["-v", "--verbose"]) {
+                    verbosity += 1;
+                }
+                let dump_frames = args.opt_value_from_str("--dump-frames")?;
+                let example = args.contains("--example");
+                let submit = args.opt_value_from_str("--submit")?;
+                let path: Option<String> = args.opt_value_from_str("--input")?;
+                let day = args.free_from_str()?;
+
+                let input = match (path, example) {
+                    (Some(_), true) => {
+                        return Err("`--input` and `--example` can not be combined.".into())
+                    }
+                    (Some(path), false) => InputSource::from_path(path),
+                    (None, true) => InputSource::Example(args.opt_free_from_str()?),
+                    (None, false) => InputSource::Input,
+                };
+
+                if submit.is_some() && input != InputSource::Input {
+                    return Err("`--submit` can only be used with the real puzzle input.".into());
+                }
+
+                AppArguments::Solve {
+                    day,
+                    release,
+                    submit,
+                    dhat,
+                    input,
+                    bench: time.then_some(bench),
+                    debug: DebugOptions {
+                        verbosity,
+                        dump_frames,
+                    },
+                }
+            }
             #[cfg(feature = "today")]
             Some("today") => AppArguments::Today,
             Some(x) => {
@@ -93,6 +160,27 @@ mod args {
 
         Ok(app_args)
     }
+
+    /// Parses the `--cold` and `--reject-outliers` bench flags.
+    fn parse_bench_options(args: &mut pico_args::Arguments) -> BenchOptions {
+        BenchOptions {
+            cold: args.contains("--cold"),
+            reject_outliers: args.contains("--reject-outliers"),
+        }
+    }
+
+    /// Parses the per-day `--timeout <seconds>` option.
+    fn parse_timeout(
+        args: &mut pico_args::Arguments,
+    ) -> Result<Option<Duration>, Box<dyn std::error::Error>> {
+        let Some(seconds) = args.opt_value_from_str::<_, f64>("--timeout")? else {
+            return Ok(None);
+        };
+
+        Duration::try_from_secs_f64(seconds)
+            .map(Some)
+            .map_err(|_| "`--timeout` expects a positive number of seconds.".into())
+    }
 }
 
 fn main() {
@@ -102,8 +190,15 @@ fn main() {
             std::process::exit(1);
         }
         Ok(args) => match args {
-            AppArguments::All { release } => all::handle(release),
-            AppArguments::Time { day, all, store } => time::handle(day, all, store),
+            AppArguments::All { release, timeout } => all::handle(release, timeout),
+            AppArguments::Time {
+                day,
+                all,
+                store,
+                bench,
+                timeout,
+            } => time::handle(day, all, store, bench, timeout),
+            AppArguments::Variants { day, bench } => time::handle_variants(day, bench),
             AppArguments::Download { day } => download::handle(day),
             AppArguments::Read { day } => read::handle(day),
             AppArguments::Scaffold {
@@ -121,7 +216,10 @@ fn main() {
                 release,
                 dhat,
                 submit,
-            } => solve::handle(day, release, dhat, submit),
+                input,
+                bench,
+                debug,
+            } => solve::handle(day, release, dhat, submit, &input, bench, &debug),
             #[cfg(feature = "today")]
             AppArguments::Today => {
                 match Day::today() {
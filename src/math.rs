@@ -0,0 +1,244 @@
+//! Number theory and decimal digit helpers.
+
+/// Decimal digits of unsigned integers.
+///
+/// All methods are correct for `0` and for the full range of the type.
+pub trait Digits: Sized {
+    /// Number of decimal digits, `1` for `0`.
+    fn digit_count(self) -> u32;
+
+    /// Splits off the lowest `count` digits, e.g. `1234.split_digits(1) == (123, 4)`.
+    fn split_digits(self, count: u32) -> (Self, Self);
+
+    /// Appends the digits of `other`, e.g. `12.concat(345) == Some(12345)`. Returns `None` on
+    /// overflow.
+    fn concat(self, other: Self) -> Option<Self>;
+
+    /// The decimal digits from most to least significant.
+    fn digits(self) -> Vec<u8>;
+}
+
+macro_rules! impl_digits {
+    ($($t:ty),*) => {$(
+        impl Digits for $t {
+            fn digit_count(self) -> u32 {
+                self.checked_ilog10().unwrap_or(0) + 1
+            }
+
+            fn split_digits(self, count: u32) -> (Self, Self) {
+                match (10 as $t).checked_pow(count) {
+                    Some(pow) => (self / pow, self % pow),
+                    None => (0, self),
+                }
+            }
+
+            fn concat(self, other: Self) -> Option<Self> {
+                (10 as $t)
+                    .checked_pow(other.digit_count())
+                    .and_then(|pow| self.checked_mul(pow))
+                    .and_then(|n| n.checked_add(other))
+            }
+
+            fn digits(self) -> Vec<u8> {
+                let mut n = self;
+                let mut digits = Vec::with_capacity(self.digit_count() as usize);
+                loop {
+                    digits.push((n % 10) as u8);
+                    n /= 10;
+                    if n == 0 {
+                        break;
+                    }
+                }
+                digits.reverse();
+                digits
+            }
+        }
+    )*};
+}
+
+impl_digits!(u8, u16, u32, u64, u128, usize);
+
+/// Greatest common divisor, `gcd(0, 0) == 0`.
+pub fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Least common multiple, `0` if either argument is `0`.
+///
+/// # Panics
+/// If the result does not fit into an `u64`.
+pub fn lcm(a: u64, b: u64) -> u64 {
+    if a == 0 || b == 0 {
+        return 0;
+    }
+    (a / gcd(a, b))
+        .checked_mul(b)
+        .expect("lcm does not fit into u64")
+}
+
+/// Extended Euclidean algorithm. Returns `(g, x, y)` with `g = gcd(a, b) >= 0` and
+/// `a * x + b * y == g`.
+///
+/// # Panics
+/// If `g` does not fit into an `i64`, which only happens for `2^63`, e.g. for
+/// `extended_gcd(i64::MIN, 0)`.
+pub fn extended_gcd(a: i64, b: i64) -> (i64, i64, i64) {
+    let (g, x, y) = extended_gcd_i128(a.into(), b.into());
+    let to_i64 = |n: i128| i64::try_from(n).expect("gcd does not fit into i64");
+    (to_i64(g), to_i64(x), to_i64(y))
+}
+
+fn extended_gcd_i128(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// The inverse of `a` modulo `m` in `0..m`, or `None` if `a` and `m` are not coprime.
+///
+/// # Panics
+/// If `m` is not positive.
+pub fn mod_inverse(a: i64, m: i64) -> Option<i64> {
+    assert!(m > 0, "modulus must be positive, got {m}");
+    let (g, x, _) = extended_gcd_i128(a.into(), m.into());
+    (g == 1).then(|| x.rem_euclid(m.into()) as i64)
+}
+
+/// Solves the system `x ≡ residue (mod modulus)` for all `(residue, modulus)` pairs with the
+/// Chinese remainder theorem. The moduli do not need to be coprime.
+///
+/// Returns the smallest non-negative solution and the modulus of all solutions, i.e. the lcm of
+/// the moduli, or `None` if the congruences contradict each other or the lcm overflows.
+///
+/// # Panics
+/// If one of the moduli is not positive.
+pub fn crt(congruences: &[(i64, i64)]) -> Option<(i64, i64)> {
+    let mut result: (i128, i128) = (0, 1);
+
+    for &(residue, modulus) in congruences {
+        assert!(modulus > 0, "modulus must be positive, got {modulus}");
+        let (r1, m1) = result;
+        let (r2, m2) = (
+            i128::from(residue).rem_euclid(modulus.into()),
+            modulus.into(),
+        );
+
+        // r1 + m1 * k ≡ r2 (mod m2)  =>  m1 * k ≡ r2 - r1 (mod m2)
+        let (g, p, _) = extended_gcd_i128(m1, m2);
+        if (r2 - r1) % g != 0 {
+            return None;
+        }
+        let step = m2 / g;
+        let k = ((r2 - r1) / g % step * (p % step)).rem_euclid(step);
+        let lcm = m1 * step;
+        if lcm > i64::MAX.into() {
+            return None;
+        }
+        result = ((r1 + m1 * k).rem_euclid(lcm), lcm);
+    }
+
+    Some((result.0 as i64, result.1 as i64))
+}
+
+/// The square root of `n` if `n` is a perfect square.
+pub fn exact_sqrt(n: u128) -> Option<u128> {
+    let root = n.isqrt();
+    (root * root == n).then_some(root)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{crt, exact_sqrt, extended_gcd, gcd, lcm, mod_inverse, Digits};
+
+    #[test]
+    fn counts_digits() {
+        assert_eq!(0u64.digit_count(), 1);
+        assert_eq!(9u64.digit_count(), 1);
+        assert_eq!(10u64.digit_count(), 2);
+        assert_eq!(u64::MAX.digit_count(), 20);
+        assert_eq!(u128::MAX.digit_count(), 39);
+        assert_eq!(1024u32.digits(), vec![1, 0, 2, 4]);
+        assert_eq!(0u8.digits(), vec![0]);
+    }
+
+    #[test]
+    fn splits_and_concatenates_digits() {
+        assert_eq!(1234u64.split_digits(2), (12, 34));
+        assert_eq!(1000u64.split_digits(2), (10, 0));
+        assert_eq!(0u64.split_digits(1), (0, 0));
+        assert_eq!(u64::MAX.split_digits(25), (0, u64::MAX));
+
+        assert_eq!(12u64.concat(345), Some(12345));
+        assert_eq!(12u64.concat(0), Some(120));
+        assert_eq!(0u64.concat(7), Some(7));
+        assert_eq!(u64::MAX.concat(1), None);
+        assert_eq!(1u128.concat(u64::MAX.into()), Some(118446744073709551615));
+    }
+
+    #[test]
+    fn computes_gcd_and_lcm() {
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(gcd(0, 5), 5);
+        assert_eq!(gcd(0, 0), 0);
+        assert_eq!(lcm(4, 6), 12);
+        assert_eq!(lcm(0, 6), 0);
+
+        let (g, x, y) = extended_gcd(240, 46);
+        assert_eq!(g, 2);
+        assert_eq!(240 * x + 46 * y, 2);
+        let (g, x, y) = extended_gcd(-6, 4);
+        assert_eq!(g, 2);
+        assert_eq!(-6 * x + 4 * y, 2);
+        assert_eq!(extended_gcd(i64::MIN, 1), (1, 0, 1));
+    }
+
+    #[test]
+    #[should_panic(expected = "gcd does not fit into i64")]
+    fn rejects_gcd_out_of_range() {
+        extended_gcd(i64::MIN, 0);
+    }
+
+    #[test]
+    fn inverts_modulo() {
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(-3, 11), Some(7));
+        assert_eq!(mod_inverse(6, 9), None);
+    }
+
+    #[test]
+    fn solves_congruences() {
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt(&[(1, 4), (3, 6)]), Some((9, 12)));
+        assert_eq!(crt(&[(1, 4), (2, 6)]), None);
+        assert_eq!(crt(&[(-1, 5)]), Some((4, 5)));
+        assert_eq!(crt(&[]), Some((0, 1)));
+    }
+
+    #[test]
+    fn takes_square_roots() {
+        assert_eq!(exact_sqrt(0), Some(0));
+        assert_eq!(exact_sqrt(49), Some(7));
+        assert_eq!(exact_sqrt(50), None);
+        assert_eq!(
+            exact_sqrt(u128::from(u64::MAX).pow(2)),
+            Some(u64::MAX.into())
+        );
+        assert_eq!(exact_sqrt(u128::MAX), None);
+    }
+}
@@ -0,0 +1,312 @@
+//! Partial orders given by "`a` comes before `b`" rules, e.g. `47|53`: a directed graph of the
+//! rules with a topological sort (Kahn's algorithm) that reports a cycle if the rules contradict
+//! each other, and an [`Order`] to sort any subset of the nodes consistently.
+//!
+//! The rules of a puzzle may only be acyclic on the subsets that are actually sorted, so a
+//! [`Dag`] can hold cycles; they are only reported by the functions that have to sort.
+
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+use std::error::Error;
+use std::fmt::{Debug, Display};
+use std::hash::Hash;
+use std::str::FromStr;
+
+use nom::bytes::complete::is_not;
+use nom::combinator::map_res;
+
+use crate::hash::{FastMap, FastSet, HashMapExt, HashSetExt};
+use crate::parse::{pair, parse_lines, ParseError};
+
+/// The rules contain a cycle, so the nodes cannot be sorted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CycleError<N> {
+    /// The nodes on the cycle: each node comes before the next one, and the last one before the
+    /// first one.
+    pub cycle: Vec<N>,
+}
+
+impl<N: Debug> Display for CycleError<N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "rules contain a cycle:")?;
+        for (i, node) in self.cycle.iter().chain(self.cycle.first()).enumerate() {
+            let arrow = if i == 0 { "" } else { " ->" };
+            write!(f, "{arrow} {node:?}")?;
+        }
+        Ok(())
+    }
+}
+
+impl<N: Debug> Error for CycleError<N> {}
+
+/// A directed graph in which an edge `before -> after` means that `before` has to come before
+/// `after`.
+#[derive(Debug, Clone)]
+pub struct Dag<N> {
+    nodes: Vec<N>,
+    index: FastMap<N, usize>,
+    successors: Vec<Vec<usize>>,
+    edges: FastSet<(usize, usize)>,
+}
+
+impl<N: Clone + Eq + Hash> Dag<N> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn node_index(&mut self, node: N) -> usize {
+        if let Some(&index) = self.index.get(&node) {
+            return index;
+        }
+        self.nodes.push(node.clone());
+        self.successors.push(vec![]);
+        self.index.insert(node, self.nodes.len() - 1);
+        self.nodes.len() - 1
+    }
+
+    /// Adds a node without rules. Nodes of edges are added automatically.
+    pub fn add_node(&mut self, node: N) {
+        self.node_index(node);
+    }
+
+    /// Adds the rule that `before` comes before `after`. Duplicate rules are ignored.
+    pub fn add_edge(&mut self, before: N, after: N) {
+        let before = self.node_index(before);
+        let after = self.node_index(after);
+        if self.edges.insert((before, after)) {
+            self.successors[before].push(after);
+        }
+    }
+
+    pub fn contains_edge(&self, before: &N, after: &N) -> bool {
+        match (self.index.get(before), self.index.get(after)) {
+            (Some(&before), Some(&after)) => self.edges.contains(&(before, after)),
+            _ => false,
+        }
+    }
+
+    /// All nodes, in the order in which they were added.
+    pub fn nodes(&self) -> &[N] {
+        &self.nodes
+    }
+
+    /// The nodes that have to come directly after `node`.
+    pub fn successors(&self, node: &N) -> impl Iterator<Item = &N> {
+        self.index
+            .get(node)
+            .into_iter()
+            .flat_map(|&index| &self.successors[index])
+            .map(|&successor| &self.nodes[successor])
+    }
+
+    /// Number of nodes.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Whether `items` break none of the rules, i.e. no item has to come before an earlier one.
+    /// Items that are not in the graph have no rules.
+    pub fn is_ordered(&self, items: &[N]) -> bool {
+        let indices: Vec<_> = items.iter().map(|item| self.index.get(item)).collect();
+        indices.iter().enumerate().all(|(i, after)| {
+            indices[i + 1..].iter().all(|before| match (before, after) {
+                (Some(&before), Some(&after)) => !self.edges.contains(&(before, after)),
+                _ => true,
+            })
+        })
+    }
+
+    /// The graph of `nodes` with all rules between them.
+    pub fn subgraph(&self, nodes: &[N]) -> Self {
+        let mut subgraph = Self::new();
+        for node in nodes {
+            subgraph.add_node(node.clone());
+        }
+        for node in nodes {
+            for successor in self.successors(node) {
+                if subgraph.index.contains_key(successor) {
+                    subgraph.add_edge(node.clone(), successor.clone());
+                }
+            }
+        }
+        subgraph
+    }
+
+    /// Sorts all nodes so that every node comes after all nodes it has a rule with, using Kahn's
+    /// algorithm. The result only depends on the order in which the nodes and rules were added.
+    pub fn topological_sort(&self) -> Result<Vec<N>, CycleError<N>> {
+        let mut in_degree = vec![0; self.len()];
+        for &(_, after) in &self.edges {
+            in_degree[after] += 1;
+        }
+
+        let mut queue: VecDeque<_> = (0..self.len()).filter(|&i| in_degree[i] == 0).collect();
+        let mut sorted = Vec::with_capacity(self.len());
+        while let Some(node) = queue.pop_front() {
+            sorted.push(node);
+            for &successor in &self.successors[node] {
+                in_degree[successor] -= 1;
+                if in_degree[successor] == 0 {
+                    queue.push_back(successor);
+                }
+            }
+        }
+
+        if sorted.len() < self.len() {
+            return Err(CycleError {
+                cycle: self.find_cycle(&in_degree),
+            });
+        }
+
+        Ok(sorted.into_iter().map(|i| self.nodes[i].clone()).collect())
+    }
+
+    /// Finds a cycle among the nodes that Kahn's algorithm could not sort, i.e. the nodes with a
+    /// remaining `in_degree`. Each of them has a predecessor that is also left, so walking back
+    /// along predecessors has to run into a cycle.
+    fn find_cycle(&self, in_degree: &[usize]) -> Vec<N> {
+        let mut predecessor = vec![None; self.len()];
+        for &(before, after) in &self.edges {
+            if in_degree[before] > 0 {
+                predecessor[after] = Some(before);
+            }
+        }
+
+        let mut path = vec![];
+        let mut position = FastMap::new();
+        let mut node = (0..self.len()).find(|&i| in_degree[i] > 0).unwrap();
+        while !position.contains_key(&node) {
+            position.insert(node, path.len());
+            path.push(node);
+            node = predecessor[node].unwrap();
+        }
+
+        path[position[&node]..]
+            .iter()
+            .rev()
+            .map(|&i| self.nodes[i].clone())
+            .collect()
+    }
+
+    /// A consistent order of `subset`, see [`Order`]. Only the rules between nodes of `subset`
+    /// are used, so this works for rules that are only acyclic on the subset.
+    pub fn order(&self, subset: &[N]) -> Result<Order<N>, CycleError<N>> {
+        let sorted = self.subgraph(subset).topological_sort()?;
+        Ok(Order {
+            rank: sorted
+                .into_iter()
+                .enumerate()
+                .map(|(rank, node)| (node, rank))
+                .collect(),
+        })
+    }
+}
+
+impl<T: Clone + Eq + Hash + FromStr> Dag<T> {
+    /// Parses one `before|after` rule per line.
+    pub fn parse_rules(input: &str) -> Result<Self, ParseError> {
+        let rules = parse_lines(input, pair("|", map_res(is_not("|\n"), str::parse)))?;
+
+        let mut dag = Self::new();
+        for (before, after) in rules {
+            dag.add_edge(before, after);
+        }
+        Ok(dag)
+    }
+}
+
+impl<N> Default for Dag<N> {
+    fn default() -> Self {
+        Self {
+            nodes: vec![],
+            index: FastMap::new(),
+            successors: vec![],
+            edges: FastSet::new(),
+        }
+    }
+}
+
+/// A total order of some nodes that agrees with all rules between them, from [`Dag::order`].
+#[derive(Debug, Clone)]
+pub struct Order<N> {
+    rank: FastMap<N, usize>,
+}
+
+impl<N: Eq + Hash> Order<N> {
+    /// Position of `node` in the order, `None` for nodes outside of it.
+    pub fn rank(&self, node: &N) -> Option<usize> {
+        self.rank.get(node).copied()
+    }
+
+    /// Compares two nodes for `sort_by`. Nodes outside of the order come last.
+    pub fn compare(&self, a: &N, b: &N) -> Ordering {
+        let rank = |node| self.rank(node).unwrap_or(usize::MAX);
+        rank(a).cmp(&rank(b))
+    }
+
+    /// Sorts `items` by the order.
+    pub fn sort(&self, items: &mut [N]) {
+        items.sort_by(|a, b| self.compare(a, b));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CycleError, Dag};
+
+    fn dag(rules: &str) -> Dag<u32> {
+        Dag::parse_rules(rules).unwrap()
+    }
+
+    #[test]
+    fn sorts_topologically() {
+        let dag = dag("3|1\n1|2\n3|2\n4|1\n");
+        assert_eq!(dag.len(), 4);
+        assert!(dag.contains_edge(&3, &1));
+        assert!(!dag.contains_edge(&1, &3));
+        assert_eq!(dag.successors(&3).collect::<Vec<_>>(), vec![&1, &2]);
+        assert_eq!(dag.topological_sort(), Ok(vec![3, 4, 1, 2]));
+
+        assert!(dag.is_ordered(&[4, 3, 1, 2]));
+        assert!(dag.is_ordered(&[3, 5, 2]));
+        assert!(!dag.is_ordered(&[1, 4]));
+    }
+
+    #[test]
+    fn reports_cycles() {
+        let dag = dag("0|1\n1|2\n2|3\n3|1\n3|4\n");
+        let mut error = dag.topological_sort().unwrap_err();
+        // any rotation of the cycle is fine.
+        let start = error.cycle.iter().position(|&n| n == 1).unwrap();
+        error.cycle.rotate_left(start);
+        assert_eq!(error.cycle, vec![1, 2, 3]);
+
+        let error = CycleError { cycle: vec![1, 2] };
+        assert_eq!(error.to_string(), "rules contain a cycle: 1 -> 2 -> 1");
+    }
+
+    #[test]
+    fn orders_subsets_of_cyclic_rules() {
+        // rock, paper, scissors: cyclic, but every pair can be sorted.
+        let dag = dag("1|2\n2|3\n3|1\n");
+        assert!(dag.topological_sort().is_err());
+
+        let order = dag.order(&[3, 1]).unwrap();
+        assert_eq!(order.rank(&3), Some(0));
+        assert_eq!(order.rank(&2), None);
+
+        let mut items = vec![2, 1, 3];
+        order.sort(&mut items);
+        assert_eq!(items, vec![3, 1, 2]);
+    }
+
+    #[test]
+    fn rejects_malformed_rules() {
+        let error = Dag::<u32>::parse_rules("1|2\n3-4\n").unwrap_err();
+        assert_eq!((error.line, error.column), (2, 1));
+    }
+}
@@ -0,0 +1,178 @@
+//! Chunked parallel map / reduce on scoped threads, for solutions that check many independent
+//! candidates.
+//!
+//! The number of threads is taken from [`set_threads`], the `AOC_THREADS` environment variable or
+//! the available parallelism, in that order. The runner reports how many threads a part used next
+//! to its timing.
+
+use std::env;
+use std::ops::Range;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+static THREADS: AtomicUsize = AtomicUsize::new(0);
+static THREADS_USED: AtomicUsize = AtomicUsize::new(0);
+
+/// Overrides the number of threads. `0` restores the default.
+pub fn set_threads(threads: usize) {
+    THREADS.store(threads, Ordering::Relaxed);
+}
+
+/// Number of threads the helpers in this module split work across.
+pub fn threads() -> usize {
+    match THREADS.load(Ordering::Relaxed) {
+        0 => env::var("AOC_THREADS")
+            .ok()
+            .and_then(|threads| threads.parse().ok())
+            .filter(|&threads| threads > 0)
+            .unwrap_or_else(|| thread::available_parallelism().map_or(1, usize::from)),
+        threads => threads,
+    }
+}
+
+/// Returns the largest number of threads used since the last call, or `0` if no helper ran.
+pub fn take_threads_used() -> usize {
+    THREADS_USED.swap(0, Ordering::Relaxed)
+}
+
+/// Splits `range` into one chunk per thread. Every thread creates its own state with `init`, maps
+/// the indices of its chunk with `map` and combines the results with `reduce`. Returns `None` for
+/// an empty range.
+///
+/// The state is useful for scratch buffers that are expensive to create, e.g. a visited set that
+/// is cleared for every item.
+pub fn map_reduce_range_with<S, R: Send>(
+    range: Range<usize>,
+    init: impl Fn() -> S + Sync,
+    map: impl Fn(&mut S, usize) -> R + Sync,
+    reduce: impl Fn(R, R) -> R + Sync,
+) -> Option<R> {
+    let len = range.len();
+    let threads = threads().clamp(1, len.max(1));
+    THREADS_USED.fetch_max(threads, Ordering::Relaxed);
+
+    let fold = |chunk: Range<usize>| {
+        let mut state = init();
+        chunk.map(|i| map(&mut state, i)).reduce(&reduce)
+    };
+
+    if threads == 1 {
+        return fold(range);
+    }
+
+    let chunk_size = len.div_ceil(threads);
+    thread::scope(|scope| {
+        let handles: Vec<_> = range
+            .clone()
+            .step_by(chunk_size)
+            .map(|start| {
+                let chunk = start..(start + chunk_size).min(range.end);
+                scope.spawn(|| fold(chunk))
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .filter_map(|handle| {
+                handle
+                    .join()
+                    .unwrap_or_else(|e| std::panic::resume_unwind(e))
+            })
+            .reduce(&reduce)
+    })
+}
+
+/// Like [`map_reduce_range_with`], without state.
+pub fn map_reduce_range<R: Send>(
+    range: Range<usize>,
+    map: impl Fn(usize) -> R + Sync,
+    reduce: impl Fn(R, R) -> R + Sync,
+) -> Option<R> {
+    map_reduce_range_with(range, || (), |_, i| map(i), reduce)
+}
+
+/// Maps all `items` in parallel and combines the results with `reduce`. Returns `None` if there
+/// are no items.
+pub fn map_reduce<T: Sync, R: Send>(
+    items: &[T],
+    map: impl Fn(&T) -> R + Sync,
+    reduce: impl Fn(R, R) -> R + Sync,
+) -> Option<R> {
+    map_reduce_range(0..items.len(), |i| map(&items[i]), reduce)
+}
+
+/// Like [`map_reduce`], with a state per thread, see [`map_reduce_range_with`].
+pub fn map_reduce_with<S, T: Sync, R: Send>(
+    items: &[T],
+    init: impl Fn() -> S + Sync,
+    map: impl Fn(&mut S, &T) -> R + Sync,
+    reduce: impl Fn(R, R) -> R + Sync,
+) -> Option<R> {
+    map_reduce_range_with(
+        0..items.len(),
+        init,
+        |state, i| map(state, &items[i]),
+        reduce,
+    )
+}
+
+/// Maps all `items` in parallel, keeping their order.
+pub fn map<T: Sync, R: Send>(items: &[T], map: impl Fn(&T) -> R + Sync) -> Vec<R> {
+    map_reduce_range(
+        0..items.len(),
+        |i| vec![map(&items[i])],
+        |mut a, mut b| {
+            a.append(&mut b);
+            a
+        },
+    )
+    .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Add;
+
+    use super::{map, map_reduce, map_reduce_range, map_reduce_with, set_threads, threads};
+
+    #[test]
+    fn reduces_in_parallel() {
+        let items: Vec<u64> = (1..=1000).collect();
+        assert_eq!(map_reduce(&items, |n| n * 2, Add::add), Some(1001000));
+        assert_eq!(map_reduce(&[] as &[u64], |n| *n, Add::add), None);
+
+        assert_eq!(map_reduce_range(0..10, |i| i, usize::max), Some(9));
+        assert_eq!(map_reduce_range(5..6, |i| i, Add::add), Some(5));
+    }
+
+    #[test]
+    fn keeps_order() {
+        let items: Vec<usize> = (0..101).collect();
+        assert_eq!(map(&items, |n| n + 1), (1..102).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn creates_state_per_thread() {
+        let items: Vec<usize> = (0..100).collect();
+        let count = map_reduce_with(
+            &items,
+            Vec::new,
+            |seen: &mut Vec<usize>, &n| {
+                seen.push(n);
+                // items of one thread form a contiguous chunk.
+                assert_eq!(seen[0] + seen.len() - 1, n);
+                1
+            },
+            Add::add,
+        );
+        assert_eq!(count, Some(100));
+    }
+
+    #[test]
+    fn configures_threads() {
+        set_threads(3);
+        assert_eq!(threads(), 3);
+        set_threads(0);
+        assert!(threads() >= 1);
+    }
+}
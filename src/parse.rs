@@ -0,0 +1,317 @@
+//! Helpers for parsing puzzle input with nom.
+//!
+//! Besides [`parse_all`], which runs a parser on the whole input and reports the position of
+//! malformed input, this module provides parsers for shapes that show up in many puzzles: numbers,
+//! separated lists, coordinate pairs, labelled values, lines and grids.
+
+use std::error::Error;
+use std::fmt::Display;
+use std::str::FromStr;
+
+use nom::{
+    bytes::complete::{tag, take_while1},
+    character::complete::{char, digit1},
+    combinator::{cut, map_res, opt, recognize},
+    error::ErrorKind,
+    multi::{many1, separated_list1},
+    sequence::{preceded, terminated},
+    IResult,
+};
+
+use crate::grid::Grid;
+
+/// An error that occurred while parsing puzzle input, pointing at the offending position.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// 1-based line of the error.
+    pub line: usize,
+    /// 1-based column (in characters) of the error.
+    pub column: usize,
+    pub message: String,
+    /// The remainder of the offending line, starting at the error position.
+    pub snippet: String,
+}
+
+impl ParseError {
+    /// Creates an error at the position of `remaining`, which must be a suffix of `input`.
+    pub fn at(input: &str, remaining: &str, message: impl Into<String>) -> Self {
+        let offset = input.len().saturating_sub(remaining.len());
+        let consumed = &input[..offset];
+
+        let line_start = consumed.rfind('\n').map_or(0, |i| i + 1);
+
+        Self {
+            line: consumed.matches('\n').count() + 1,
+            column: consumed[line_start..].chars().count() + 1,
+            message: message.into(),
+            snippet: remaining.lines().next().unwrap_or_default().to_string(),
+        }
+    }
+
+    /// Creates an error from a nom error that occurred while parsing `input`.
+    pub fn from_nom(input: &str, err: nom::Err<nom::error::Error<&str>>) -> Self {
+        match err {
+            nom::Err::Incomplete(_) => Self::at(input, "", "unexpected end of input"),
+            nom::Err::Error(e) | nom::Err::Failure(e) => Self::at(input, e.input, describe(e.code)),
+        }
+    }
+}
+
+fn describe(kind: ErrorKind) -> String {
+    match kind {
+        ErrorKind::Digit => "expected a number".into(),
+        ErrorKind::MapRes => "invalid value".into(),
+        ErrorKind::Eof => "unexpected input".into(),
+        kind => format!("expected {kind:?}"),
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )?;
+
+        if !self.snippet.is_empty() {
+            write!(f, " near \"{}\"", self.snippet)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Error for ParseError {}
+
+/// Runs `parser` on the whole `input`.
+///
+/// Fails if the parser fails or leaves anything but whitespace unconsumed, e.g. because a
+/// `many1` stopped at a malformed line.
+pub fn parse_all<'a, O>(
+    input: &'a str,
+    mut parser: impl FnMut(&'a str) -> IResult<&'a str, O>,
+) -> Result<O, ParseError> {
+    match parser(input) {
+        Ok((remaining, output)) if remaining.trim().is_empty() => Ok(output),
+        Ok((remaining, _)) => Err(ParseError::at(input, remaining, "unexpected input")),
+        Err(e) => Err(ParseError::from_nom(input, e)),
+    }
+}
+
+/// Runs `parser` on every line of `input`, see [`lines`] and [`parse_all`].
+pub fn parse_lines<'a, O>(
+    input: &'a str,
+    parser: impl FnMut(&'a str) -> IResult<&'a str, O>,
+) -> Result<Vec<O>, ParseError> {
+    parse_all(input, lines(parser))
+}
+
+/// Parses an unsigned integer, e.g. `42`. Fails for values that do not fit into `T`.
+pub fn unsigned<T: FromStr>(input: &str) -> IResult<&str, T> {
+    map_res(digit1, str::parse)(input)
+}
+
+/// Parses an integer with an optional leading `-`, e.g. `-42`. Fails for values that do not fit
+/// into `T`, including negative values for unsigned types.
+pub fn number<T: FromStr>(input: &str) -> IResult<&str, T> {
+    map_res(recognize(preceded(opt(char('-')), digit1)), str::parse)(input)
+}
+
+/// Parses one or more items separated by `separator`, e.g. `1,2,3`.
+pub fn list<'a, O>(
+    separator: &'static str,
+    item: impl FnMut(&'a str) -> IResult<&'a str, O>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<O>> {
+    separated_list1(tag(separator), item)
+}
+
+/// Parses two items separated by `separator` with the same parser, e.g. `3x4`.
+pub fn pair<'a, O>(
+    separator: &'static str,
+    mut item: impl FnMut(&'a str) -> IResult<&'a str, O>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, (O, O)> {
+    move |input| {
+        let (input, first) = item(input)?;
+        let (input, _) = tag(separator)(input)?;
+        let (input, second) = item(input)?;
+        Ok((input, (first, second)))
+    }
+}
+
+/// Parses a coordinate pair like `3,-4`.
+pub fn coordinates<T: FromStr>(input: &str) -> IResult<&str, (T, T)> {
+    pair(",", number)(input)
+}
+
+/// Parses `label` followed by `value`, e.g. `labelled("Register A: ", number)`.
+pub fn labelled<'a, O>(
+    label: &'static str,
+    value: impl FnMut(&'a str) -> IResult<&'a str, O>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, O> {
+    preceded(tag(label), value)
+}
+
+/// Parses one or more lines with `line`, up to the end of the input or trailing whitespace. Every
+/// line must be terminated by a newline. A malformed line fails the whole parser, so the error
+/// points at the offending position within the line instead of at its start.
+pub fn lines<'a, O>(
+    mut line: impl FnMut(&'a str) -> IResult<&'a str, O>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<O>> {
+    move |mut input: &'a str| {
+        let mut items = vec![];
+        while items.is_empty() || !input.trim().is_empty() {
+            let (remaining, item) = cut(terminated(&mut line, char('\n')))(input)?;
+            items.push(item);
+            input = remaining;
+        }
+        Ok((input, items))
+    }
+}
+
+/// Parses a block of non-empty lines into a grid, see [`Grid::try_parse`]. The block ends at a
+/// blank line or at the end of the input.
+pub fn grid<'a, T>(
+    mut cell: impl FnMut(char) -> Option<T>,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Grid<T>> {
+    move |input: &'a str| {
+        let (remaining, block) =
+            recognize(many1(terminated(take_while1(|c| c != '\n'), char('\n'))))(input)?;
+
+        match Grid::try_parse(block, &mut cell) {
+            Ok(grid) => Ok((remaining, grid)),
+            Err(e) => {
+                // point the nom error at the offending character in the block.
+                let line_start: usize = block.lines().take(e.line - 1).map(|l| l.len() + 1).sum();
+                let column: usize = block[line_start..]
+                    .chars()
+                    .take(e.column - 1)
+                    .map(char::len_utf8)
+                    .sum();
+                Err(nom::Err::Failure(nom::error::Error::new(
+                    &input[line_start + column..],
+                    ErrorKind::Verify,
+                )))
+            }
+        }
+    }
+}
+
+/// Splits `input` into blocks that are separated by blank lines.
+/// Every returned section ends with a single newline, like the normalized input.
+pub fn sections(input: &str) -> impl Iterator<Item = &str> {
+    let mut rest = input;
+    std::iter::from_fn(move || {
+        rest = rest.trim_start_matches('\n');
+        if rest.is_empty() {
+            return None;
+        }
+        let end = rest.find("\n\n").map_or(rest.len(), |i| i + 1);
+        let (section, remaining) = rest.split_at(end);
+        rest = remaining;
+        Some(section)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use nom::{
+        bytes::complete::tag, character::complete::digit1, multi::many1, sequence::terminated,
+    };
+
+    use super::{
+        coordinates, grid, labelled, list, number, pair, parse_all, parse_lines, sections,
+        unsigned, ParseError,
+    };
+
+    fn numbers(input: &str) -> nom::IResult<&str, Vec<&str>> {
+        many1(terminated(digit1, tag("\n")))(input)
+    }
+
+    #[test]
+    fn parses_complete_input() {
+        assert_eq!(parse_all("1\n2\n", numbers), Ok(vec!["1", "2"]));
+    }
+
+    #[test]
+    fn reports_position_of_trailing_input() {
+        let err = parse_all("1\n2\nx3\n", numbers).unwrap_err();
+        assert_eq!((err.line, err.column), (3, 1));
+        assert_eq!(err.snippet, "x3");
+    }
+
+    #[test]
+    fn reports_position_of_parser_errors() {
+        let err = parse_all("12a\n", numbers).unwrap_err();
+        assert_eq!((err.line, err.column), (1, 3));
+        assert_eq!(err.to_string(), "line 1, column 3: expected Tag near \"a\"");
+    }
+
+    #[test]
+    fn counts_columns_in_characters() {
+        let err = ParseError::at("äö\nüx", "x", "bad");
+        assert_eq!((err.line, err.column), (2, 2));
+    }
+
+    #[test]
+    fn parses_numbers() {
+        assert_eq!(number::<i32>("-42,"), Ok((",", -42)));
+        assert_eq!(number::<i64>("42"), Ok(("", 42)));
+        assert_eq!(unsigned::<u64>("18446744073709551615"), Ok(("", u64::MAX)));
+        assert!(number::<u32>("-1").is_err());
+        assert!(unsigned::<u8>("256").is_err());
+        assert!(unsigned::<u32>("-1").is_err());
+    }
+
+    #[test]
+    fn parses_lists_and_pairs() {
+        assert_eq!(
+            list(",", unsigned::<u8>)("1,2,3\n"),
+            Ok(("\n", vec![1, 2, 3]))
+        );
+        assert_eq!(coordinates::<i32>("3,-4 "), Ok((" ", (3, -4))));
+        assert_eq!(pair("x", unsigned::<u32>)("3x4"), Ok(("", (3, 4))));
+        assert_eq!(
+            labelled("Register A: ", unsigned::<u64>)("Register A: 729"),
+            Ok(("", 729))
+        );
+    }
+
+    #[test]
+    fn parses_lines_with_errors() {
+        assert_eq!(
+            parse_lines("1,2\n3,4\n", coordinates::<u32>),
+            Ok(vec![(1, 2), (3, 4)])
+        );
+
+        let err = parse_lines("1,2\n3,x\n", coordinates::<u32>).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 3));
+        assert_eq!(err.message, "expected a number");
+        assert_eq!(err.snippet, "x");
+
+        let err = parse_all("x", unsigned::<u32>).unwrap_err();
+        assert_eq!(err.message, "expected a number");
+    }
+
+    #[test]
+    fn splits_sections() {
+        let input = "a\nb\n\nc\n\n\nd\n";
+        assert_eq!(
+            sections(input).collect::<Vec<_>>(),
+            vec!["a\nb\n", "c\n", "d\n"]
+        );
+    }
+
+    #[test]
+    fn parses_grid_blocks() {
+        let cell = |c| matches!(c, '.' | '#').then_some(c == '#');
+
+        let (remaining, parsed) = grid(cell)("#.\n.#\n\nrest").unwrap();
+        assert_eq!(remaining, "\nrest");
+        assert_eq!((parsed.width(), parsed.height()), (2, 2));
+        assert!(parsed[(1, 1)]);
+
+        let err = parse_all("..\n.x\n", grid(cell)).unwrap_err();
+        assert_eq!((err.line, err.column), (2, 2));
+    }
+}
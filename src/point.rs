@@ -0,0 +1,230 @@
+//! Points and vectors on an integer plane.
+//!
+//! As in [`crate::grid::Grid`], `x` grows to the right and `y` grows downwards.
+
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+/// A position on the plane.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point {
+    pub x: i64,
+    pub y: i64,
+}
+
+/// A displacement between two points, e.g. a velocity or a step in a direction.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Vector {
+    pub x: i64,
+    pub y: i64,
+}
+
+impl Point {
+    pub const ORIGIN: Point = Point { x: 0, y: 0 };
+
+    pub const fn new(x: i64, y: i64) -> Self {
+        Self { x, y }
+    }
+
+    /// Manhattan (taxicab) distance to `other`.
+    pub fn manhattan(self, other: Point) -> i64 {
+        (other - self).manhattan()
+    }
+
+    /// Chebyshev (king's move) distance to `other`.
+    pub fn chebyshev(self, other: Point) -> i64 {
+        (other - self).chebyshev()
+    }
+
+    /// Wraps the point into the rectangle `0..size.x` x `0..size.y`, e.g. for maps that wrap
+    /// around at the edges.
+    pub fn rem_euclid(self, size: Vector) -> Point {
+        Point::new(self.x.rem_euclid(size.x), self.y.rem_euclid(size.y))
+    }
+
+    /// The `(x, y)` grid position of this point, or `None` if a coordinate is negative.
+    pub fn to_position(self) -> Option<(usize, usize)> {
+        Some((usize::try_from(self.x).ok()?, usize::try_from(self.y).ok()?))
+    }
+
+    /// Index of this point in a flat, row-major grid of `width` columns, or `None` if the point
+    /// lies outside of the columns.
+    pub fn grid_index(self, width: usize) -> Option<usize> {
+        let (x, y) = self.to_position()?;
+        (x < width).then(|| y * width + x)
+    }
+}
+
+impl Vector {
+    pub const ZERO: Vector = Vector { x: 0, y: 0 };
+
+    pub const fn new(x: i64, y: i64) -> Self {
+        Self { x, y }
+    }
+
+    /// Manhattan length of the vector.
+    pub fn manhattan(self) -> i64 {
+        self.x.abs() + self.y.abs()
+    }
+
+    /// Chebyshev length of the vector.
+    pub fn chebyshev(self) -> i64 {
+        self.x.abs().max(self.y.abs())
+    }
+
+    /// Rotates the vector by 90 degrees clockwise (on screen, i.e. with `y` pointing down).
+    pub fn rotate_right(self) -> Vector {
+        Vector::new(-self.y, self.x)
+    }
+
+    /// Rotates the vector by 90 degrees counterclockwise (on screen).
+    pub fn rotate_left(self) -> Vector {
+        Vector::new(self.y, -self.x)
+    }
+}
+
+impl From<(usize, usize)> for Point {
+    fn from((x, y): (usize, usize)) -> Self {
+        Point::new(x as i64, y as i64)
+    }
+}
+
+impl From<(i64, i64)> for Point {
+    fn from((x, y): (i64, i64)) -> Self {
+        Point::new(x, y)
+    }
+}
+
+impl From<(i64, i64)> for Vector {
+    fn from((x, y): (i64, i64)) -> Self {
+        Vector::new(x, y)
+    }
+}
+
+impl Add<Vector> for Point {
+    type Output = Point;
+
+    fn add(self, rhs: Vector) -> Self::Output {
+        Point::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl AddAssign<Vector> for Point {
+    fn add_assign(&mut self, rhs: Vector) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub<Vector> for Point {
+    type Output = Point;
+
+    fn sub(self, rhs: Vector) -> Self::Output {
+        Point::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl SubAssign<Vector> for Point {
+    fn sub_assign(&mut self, rhs: Vector) {
+        *self = *self - rhs;
+    }
+}
+
+impl Sub for Point {
+    type Output = Vector;
+
+    fn sub(self, rhs: Point) -> Self::Output {
+        Vector::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl Add for Vector {
+    type Output = Vector;
+
+    fn add(self, rhs: Vector) -> Self::Output {
+        Vector::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl AddAssign for Vector {
+    fn add_assign(&mut self, rhs: Vector) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for Vector {
+    type Output = Vector;
+
+    fn sub(self, rhs: Vector) -> Self::Output {
+        Vector::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl SubAssign for Vector {
+    fn sub_assign(&mut self, rhs: Vector) {
+        *self = *self - rhs;
+    }
+}
+
+impl Mul<i64> for Vector {
+    type Output = Vector;
+
+    fn mul(self, rhs: i64) -> Self::Output {
+        Vector::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+impl Neg for Vector {
+    type Output = Vector;
+
+    fn neg(self) -> Self::Output {
+        Vector::new(-self.x, -self.y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Point, Vector};
+
+    #[test]
+    fn does_arithmetic() {
+        let a = Point::new(1, 2);
+        let b = Point::new(4, -2);
+        assert_eq!(b - a, Vector::new(3, -4));
+        assert_eq!(a + (b - a), b);
+        assert_eq!(a - Vector::new(1, 1), Point::new(0, 1));
+        assert_eq!(Vector::new(3, -4) * 2, Vector::new(6, -8));
+        assert_eq!(-Vector::new(3, -4), Vector::new(-3, 4));
+    }
+
+    #[test]
+    fn measures_distances() {
+        let a = Point::new(1, 2);
+        let b = Point::new(4, -2);
+        assert_eq!(a.manhattan(b), 7);
+        assert_eq!(a.chebyshev(b), 4);
+        assert_eq!(b.manhattan(a), 7);
+    }
+
+    #[test]
+    fn rotates_vectors() {
+        let up = Vector::new(0, -1);
+        assert_eq!(up.rotate_right(), Vector::new(1, 0));
+        assert_eq!(up.rotate_left(), Vector::new(-1, 0));
+        assert_eq!(up.rotate_right().rotate_right(), -up);
+    }
+
+    #[test]
+    fn converts_to_grid_positions() {
+        assert_eq!(Point::new(2, 3).to_position(), Some((2, 3)));
+        assert_eq!(Point::new(-1, 3).to_position(), None);
+        assert_eq!(Point::new(2, 3).grid_index(5), Some(17));
+        assert_eq!(Point::new(5, 3).grid_index(5), None);
+        assert_eq!(Point::from((2usize, 3usize)), Point::new(2, 3));
+    }
+
+    #[test]
+    fn wraps_around() {
+        let size = Vector::new(11, 7);
+        assert_eq!(Point::new(-1, 7).rem_euclid(size), Point::new(10, 0));
+        assert_eq!(Point::new(23, -15).rem_euclid(size), Point::new(1, 6));
+    }
+}
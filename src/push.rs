@@ -0,0 +1,250 @@
+//! Sokoban-style pushing of objects on a grid with walls, e.g. a robot that pushes crates.
+//!
+//! Objects occupy one or more cells, their footprint. Pushing an object also pushes every object
+//! in its way, which may in turn push objects in other rows or columns. A push is atomic: it only
+//! happens if none of the pushed objects would run into a wall or leave the grid.
+
+use crate::direction::Direction;
+use crate::grid::Grid;
+use crate::point::{Point, Vector};
+
+/// Identifies an object, in the order in which the objects were added.
+pub type ObjectId = usize;
+
+/// An object that can be pushed, with a `kind` chosen by the caller.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Object<K> {
+    pub kind: K,
+    pub position: Point,
+    /// Offsets of the occupied cells from `position`.
+    pub footprint: Vec<Vector>,
+}
+
+impl<K> Object<K> {
+    /// The cells the object occupies.
+    pub fn cells(&self) -> impl Iterator<Item = Point> + '_ {
+        self.footprint.iter().map(|&offset| self.position + offset)
+    }
+}
+
+/// A grid of walls and objects. Everything outside of the grid counts as wall.
+#[derive(Debug, Clone)]
+pub struct PushGrid<K> {
+    walls: Grid<bool>,
+    occupants: Grid<Option<ObjectId>>,
+    objects: Vec<Object<K>>,
+}
+
+impl<K> PushGrid<K> {
+    /// Creates an empty `width` x `height` grid.
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            walls: Grid::new(width, height, false),
+            occupants: Grid::new(width, height, None),
+            objects: vec![],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.walls.width()
+    }
+
+    pub fn height(&self) -> usize {
+        self.walls.height()
+    }
+
+    /// # Panics
+    /// If `position` lies outside of the grid.
+    pub fn add_wall(&mut self, position: (usize, usize)) {
+        self.walls[position] = true;
+    }
+
+    pub fn is_wall(&self, point: Point) -> bool {
+        self.walls.get_point(point).copied().unwrap_or(true)
+    }
+
+    /// Adds an object that occupies the cells `position + offset` for all offsets in
+    /// `footprint`.
+    ///
+    /// # Panics
+    /// If one of the cells is a wall or already occupied.
+    pub fn add_object(&mut self, kind: K, position: Point, footprint: Vec<Vector>) -> ObjectId {
+        let id = self.objects.len();
+        let object = Object {
+            kind,
+            position,
+            footprint,
+        };
+
+        for cell in object.cells() {
+            assert!(
+                !self.is_wall(cell) && self.object_at(cell).is_none(),
+                "cell {cell:?} of object {id} is blocked"
+            );
+            self.occupants[cell] = Some(id);
+        }
+
+        self.objects.push(object);
+        id
+    }
+
+    pub fn object(&self, id: ObjectId) -> &Object<K> {
+        &self.objects[id]
+    }
+
+    /// All objects with their ids.
+    pub fn objects(&self) -> impl Iterator<Item = (ObjectId, &Object<K>)> {
+        self.objects.iter().enumerate()
+    }
+
+    /// The object that occupies `point`, if any.
+    pub fn object_at(&self, point: Point) -> Option<ObjectId> {
+        self.occupants.get_point(point).copied().flatten()
+    }
+
+    /// All objects that move if object `id` is pushed one step in `direction`, starting with
+    /// `id` itself, or `None` if one of them is blocked by a wall.
+    pub fn push_set(&self, id: ObjectId, direction: Direction) -> Option<Vec<ObjectId>> {
+        let offset = direction.offset();
+        let mut pushed = vec![false; self.objects.len()];
+        pushed[id] = true;
+
+        let mut set = vec![id];
+        let mut i = 0;
+        while let Some(&current) = set.get(i) {
+            for cell in self.objects[current].cells() {
+                let next = cell + offset;
+                if self.is_wall(next) {
+                    return None;
+                }
+                if let Some(other) = self.object_at(next) {
+                    if !pushed[other] {
+                        pushed[other] = true;
+                        set.push(other);
+                    }
+                }
+            }
+            i += 1;
+        }
+
+        Some(set)
+    }
+
+    /// Pushes object `id` one step in `direction`, together with all objects in its way.
+    /// Returns `false` and changes nothing if the objects are blocked.
+    pub fn push(&mut self, id: ObjectId, direction: Direction) -> bool {
+        let Some(set) = self.push_set(id, direction) else {
+            return false;
+        };
+
+        // clear all cells first, so objects can move into cells that another one leaves.
+        for &id in &set {
+            for cell in self.objects[id].cells() {
+                self.occupants[cell] = None;
+            }
+        }
+        for &id in &set {
+            let object = &mut self.objects[id];
+            object.position += direction.offset();
+            for cell in object.cells() {
+                self.occupants[cell] = Some(id);
+            }
+        }
+
+        true
+    }
+
+    /// Draws the grid, e.g. to print it or to export an image. `object` is called for every
+    /// occupied cell with the object and the offset of the cell in its footprint.
+    pub fn render<T: Clone>(
+        &self,
+        wall: T,
+        empty: T,
+        mut object: impl FnMut(&Object<K>, Vector) -> T,
+    ) -> Grid<T> {
+        let mut grid = self.walls.map(
+            |&is_wall| {
+                if is_wall {
+                    wall.clone()
+                } else {
+                    empty.clone()
+                }
+            },
+        );
+        for current in &self.objects {
+            for &offset in &current.footprint {
+                grid[current.position + offset] = object(current, offset);
+            }
+        }
+        grid
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PushGrid;
+    use crate::direction::Direction;
+    use crate::point::{Point, Vector};
+
+    const SINGLE: [Vector; 1] = [Vector::ZERO];
+    const WIDE: [Vector; 2] = [Vector::ZERO, Vector::new(1, 0)];
+
+    fn draw(grid: &PushGrid<char>) -> String {
+        grid.render('#', '.', |object, offset| match object.kind {
+            'O' if offset.x == 0 => '[',
+            'O' => ']',
+            kind => kind,
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn pushes_rows_of_objects() {
+        let mut grid = PushGrid::new(5, 1);
+        grid.add_wall((4, 0));
+        let robot = grid.add_object('@', Point::new(0, 0), SINGLE.to_vec());
+        grid.add_object('o', Point::new(1, 0), SINGLE.to_vec());
+        grid.add_object('o', Point::new(2, 0), SINGLE.to_vec());
+
+        assert_eq!(grid.push_set(robot, Direction::Right), Some(vec![0, 1, 2]));
+        assert!(grid.push(robot, Direction::Right));
+        assert_eq!(draw(&grid), ".@oo#\n");
+
+        // blocked by the wall, nothing moves.
+        assert!(!grid.push(robot, Direction::Right));
+        assert_eq!(grid.object(robot).position, Point::new(1, 0));
+
+        // the edge of the grid counts as wall.
+        assert!(grid.push(robot, Direction::Left));
+        assert!(!grid.push(robot, Direction::Left));
+        assert_eq!(grid.object_at(Point::new(0, 0)), Some(robot));
+    }
+
+    #[test]
+    fn pushes_wide_objects_across_rows() {
+        let mut grid = PushGrid::new(6, 5);
+        let robot = grid.add_object('@', Point::new(2, 4), SINGLE.to_vec());
+        grid.add_object('O', Point::new(2, 3), WIDE.to_vec());
+        grid.add_object('O', Point::new(1, 2), WIDE.to_vec());
+        grid.add_object('O', Point::new(3, 2), WIDE.to_vec());
+        assert_eq!(draw(&grid), "......\n......\n.[][].\n..[]..\n..@...\n");
+
+        assert_eq!(grid.push_set(robot, Direction::Up).unwrap().len(), 4);
+        assert!(grid.push(robot, Direction::Up));
+        assert_eq!(draw(&grid), "......\n.[][].\n..[]..\n..@...\n......\n");
+
+        // one blocked object blocks the whole push.
+        grid.add_wall((4, 0));
+        assert_eq!(grid.push_set(robot, Direction::Up), None);
+        assert!(!grid.push(robot, Direction::Up));
+        assert_eq!(draw(&grid), "....#.\n.[][].\n..[]..\n..@...\n......\n");
+    }
+
+    #[test]
+    #[should_panic]
+    fn rejects_overlapping_objects() {
+        let mut grid = PushGrid::new(3, 1);
+        grid.add_object('O', Point::new(0, 0), WIDE.to_vec());
+        grid.add_object('O', Point::new(1, 0), WIDE.to_vec());
+    }
+}
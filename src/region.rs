@@ -0,0 +1,198 @@
+//! Connected-component labelling on grids, e.g. for puzzles about fenced regions.
+
+use crate::grid::{Grid, OFFSETS_4};
+
+/// The smallest rectangle containing all cells of a region, with inclusive corners.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bounds {
+    pub min: (usize, usize),
+    pub max: (usize, usize),
+}
+
+impl Bounds {
+    pub fn width(&self) -> usize {
+        self.max.0 - self.min.0 + 1
+    }
+
+    pub fn height(&self) -> usize {
+        self.max.1 - self.min.1 + 1
+    }
+}
+
+/// A connected region of cells.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Region {
+    pub id: usize,
+    /// Number of cells.
+    pub area: usize,
+    /// Number of cell edges that border another region or the edge of the grid.
+    pub perimeter: usize,
+    /// Number of straight sides of the region's outline, including the outlines of holes.
+    pub sides: usize,
+    pub bounds: Bounds,
+}
+
+/// The regions of a grid, see [`label`].
+#[derive(Debug, Clone)]
+pub struct Regions {
+    labels: Grid<usize>,
+    regions: Vec<Region>,
+}
+
+impl Regions {
+    /// Id of the region that contains `position`.
+    pub fn id(&self, position: (usize, usize)) -> usize {
+        self.labels[position]
+    }
+
+    /// All regions, indexed by id.
+    pub fn regions(&self) -> &[Region] {
+        &self.regions
+    }
+
+    /// A grid of the same size as the labelled grid that contains the region id of every cell.
+    pub fn labels(&self) -> &Grid<usize> {
+        &self.labels
+    }
+
+    /// Positions of all cells of the region with `id`.
+    pub fn positions(&self, id: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.labels
+            .iter()
+            .filter(move |(_, label)| **label == id)
+            .map(|(position, _)| position)
+    }
+}
+
+/// Splits `grid` into regions of orthogonally connected cells for which `same` returns `true`
+/// between neighbours, e.g. `|a, b| a == b` for regions of equal plants.
+///
+/// Region ids are assigned in row-major order of the regions' first cells.
+pub fn label<T>(grid: &Grid<T>, mut same: impl FnMut(&T, &T) -> bool) -> Regions {
+    const UNLABELLED: usize = usize::MAX;
+
+    let mut labels = Grid::new(grid.width(), grid.height(), UNLABELLED);
+    let mut regions = vec![];
+
+    for start in grid.positions() {
+        if labels[start] != UNLABELLED {
+            continue;
+        }
+
+        let id = regions.len();
+        let mut bounds = Bounds {
+            min: start,
+            max: start,
+        };
+        let mut area = 0;
+
+        labels[start] = id;
+        let mut stack = vec![start];
+
+        while let Some(position) = stack.pop() {
+            area += 1;
+            bounds.min = (bounds.min.0.min(position.0), bounds.min.1.min(position.1));
+            bounds.max = (bounds.max.0.max(position.0), bounds.max.1.max(position.1));
+
+            for neighbour in grid.neighbours_4(position) {
+                if labels[neighbour] == UNLABELLED && same(&grid[position], &grid[neighbour]) {
+                    labels[neighbour] = id;
+                    stack.push(neighbour);
+                }
+            }
+        }
+
+        regions.push(Region {
+            id,
+            area,
+            perimeter: 0,
+            sides: 0,
+            bounds,
+        });
+    }
+
+    for (position, &id) in labels.iter() {
+        let same_region = |offset| {
+            labels
+                .offset(position, offset)
+                .is_some_and(|other| labels[other] == id)
+        };
+
+        let region = &mut regions[id];
+
+        for (i, offset) in OFFSETS_4.into_iter().enumerate() {
+            let outside = !same_region(offset);
+            region.perimeter += usize::from(outside);
+
+            // every corner of the outline starts a new side. Check the corner between this edge
+            // and the next one clockwise.
+            let next = OFFSETS_4[(i + 1) % 4];
+            let diagonal = (offset.0 + next.0, offset.1 + next.1);
+            let convex = outside && !same_region(next);
+            let concave = !outside && same_region(next) && !same_region(diagonal);
+            region.sides += usize::from(convex || concave);
+        }
+    }
+
+    Regions { labels, regions }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{label, Bounds};
+    use crate::grid::Grid;
+
+    fn regions(input: &str) -> super::Regions {
+        label(&Grid::parse(input, |c| c).unwrap(), |a, b| a == b)
+    }
+
+    #[test]
+    fn labels_connected_cells() {
+        let regions = regions("AAB\nABB\nCAA\n");
+        assert_eq!(regions.regions().len(), 4);
+        assert_eq!(regions.id((0, 0)), regions.id((0, 1)));
+        assert_ne!(regions.id((0, 0)), regions.id((1, 2)));
+        assert_eq!(regions.id((1, 2)), regions.id((2, 2)));
+        assert_eq!(regions.positions(regions.id((2, 0))).count(), 3);
+    }
+
+    #[test]
+    fn measures_regions() {
+        // the example from the puzzle of 2024 day 12.
+        let regions = regions("AAAA\nBBCD\nBBCC\nEEEC\n");
+        let by_plant = |position| &regions.regions()[regions.id(position)];
+
+        let a = by_plant((0, 0));
+        assert_eq!((a.area, a.perimeter, a.sides), (4, 10, 4));
+
+        let c = by_plant((2, 1));
+        assert_eq!((c.area, c.perimeter, c.sides), (4, 10, 8));
+        assert_eq!(
+            c.bounds,
+            Bounds {
+                min: (2, 1),
+                max: (3, 3)
+            }
+        );
+        assert_eq!((c.bounds.width(), c.bounds.height()), (2, 3));
+
+        let d = by_plant((3, 1));
+        assert_eq!((d.area, d.perimeter, d.sides), (1, 4, 4));
+    }
+
+    #[test]
+    fn counts_sides_of_holes() {
+        let regions = regions("OOOOO\nOXOXO\nOOOOO\nOXOXO\nOOOOO\n");
+        let outer = &regions.regions()[regions.id((0, 0))];
+        assert_eq!(outer.area, 21);
+        assert_eq!(outer.sides, 20);
+        assert_eq!(outer.perimeter, 36);
+    }
+
+    #[test]
+    fn handles_diagonal_touching_regions() {
+        let regions = regions("AB\nBA\n");
+        assert_eq!(regions.regions().len(), 4);
+        assert!(regions.regions().iter().all(|r| r.sides == 4));
+    }
+}
@@ -0,0 +1,434 @@
+//! Graph searches over implicit graphs, given by a start node and a neighbour function.
+//!
+//! Nodes can be anything that is `Clone + Eq + Hash`, e.g. grid positions or `(position, direction)`
+//! states. Weighted searches take neighbours as `(node, cost)` pairs with any cost type that can be
+//! added and compared, with `C::default()` as zero cost.
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::{BuildHasher, Hash};
+use std::ops::Add;
+
+use crate::hash::{FastMap, FastSet, HashSetExt};
+
+/// A path found by a search, from the start node to the goal (both included).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path<N, C> {
+    pub nodes: Vec<N>,
+    pub cost: C,
+}
+
+/// Follows `parents` from `goal` back to the node without a parent (the start).
+/// Returns the path in order from start to goal.
+pub fn reconstruct_path<N: Clone + Eq + Hash, S: BuildHasher>(
+    parents: &HashMap<N, Option<N>, S>,
+    goal: N,
+) -> Vec<N> {
+    let mut path = vec![goal];
+    while let Some(Some(parent)) = parents.get(&path[path.len() - 1]) {
+        path.push(parent.clone());
+    }
+    path.reverse();
+    path
+}
+
+/// Breadth-first search for the shortest path (in steps) from `start` to a node that satisfies
+/// `is_goal`.
+pub fn bfs<N, I>(
+    start: N,
+    mut neighbours: impl FnMut(&N) -> I,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Option<Path<N, usize>>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut parents = FastMap::from_iter([(start.clone(), None)]);
+    let mut queue = VecDeque::from([(start, 0)]);
+
+    while let Some((node, steps)) = queue.pop_front() {
+        if is_goal(&node) {
+            return Some(Path {
+                nodes: reconstruct_path(&parents, node),
+                cost: steps,
+            });
+        }
+
+        for next in neighbours(&node) {
+            if !parents.contains_key(&next) {
+                parents.insert(next.clone(), Some(node.clone()));
+                queue.push_back((next, steps + 1));
+            }
+        }
+    }
+
+    None
+}
+
+/// Number of steps from `start` to every reachable node.
+pub fn bfs_distances<N, I>(start: N, mut neighbours: impl FnMut(&N) -> I) -> FastMap<N, usize>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut distances = FastMap::from_iter([(start.clone(), 0)]);
+    let mut queue = VecDeque::from([(start, 0)]);
+
+    while let Some((node, steps)) = queue.pop_front() {
+        for next in neighbours(&node) {
+            if !distances.contains_key(&next) {
+                distances.insert(next.clone(), steps + 1);
+                queue.push_back((next, steps + 1));
+            }
+        }
+    }
+
+    distances
+}
+
+/// Dijkstra's algorithm for the cheapest path from `start` to a node that satisfies `is_goal`.
+pub fn dijkstra<N, C, I>(
+    start: N,
+    neighbours: impl FnMut(&N) -> I,
+    is_goal: impl FnMut(&N) -> bool,
+) -> Option<Path<N, C>>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Add<Output = C> + Default,
+    I: IntoIterator<Item = (N, C)>,
+{
+    astar(start, neighbours, |_| C::default(), is_goal)
+}
+
+/// A* search for the cheapest path from `start` to a node that satisfies `is_goal`.
+///
+/// `heuristic` estimates the remaining cost to the goal. Expanded nodes are never reopened, so the
+/// result is only guaranteed to be optimal if the heuristic is consistent: it never decreases by
+/// more than the cost of a step, e.g. the Manhattan distance on a grid with unit steps.
+pub fn astar<N, C, I>(
+    start: N,
+    mut neighbours: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> C,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Option<Path<N, C>>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Add<Output = C> + Default,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let mut nodes = Nodes::new(start.clone());
+    let mut costs = vec![C::default()];
+    let mut parents: Vec<Option<usize>> = vec![None];
+    let mut closed = vec![false];
+
+    // entries are (estimated total cost, cost so far, node id).
+    let mut queue = BinaryHeap::from([Reverse((heuristic(&start), C::default(), 0))]);
+
+    while let Some(Reverse((_, cost, id))) = queue.pop() {
+        if closed[id] || cost > costs[id] {
+            continue;
+        }
+        closed[id] = true;
+
+        if is_goal(&nodes.nodes[id]) {
+            let mut path = vec![id];
+            while let Some(parent) = parents[path[path.len() - 1]] {
+                path.push(parent);
+            }
+            return Some(Path {
+                nodes: path
+                    .into_iter()
+                    .rev()
+                    .map(|id| nodes.nodes[id].clone())
+                    .collect(),
+                cost,
+            });
+        }
+
+        let node = nodes.nodes[id].clone();
+        for (next, step_cost) in neighbours(&node) {
+            let next_cost = cost + step_cost;
+            let (next_id, is_new) = nodes.id(next);
+
+            if is_new {
+                costs.push(next_cost);
+                parents.push(Some(id));
+                closed.push(false);
+            } else if next_cost < costs[next_id] {
+                costs[next_id] = next_cost;
+                parents[next_id] = Some(id);
+            } else {
+                continue;
+            }
+
+            let estimate = next_cost + heuristic(&nodes.nodes[next_id]);
+            queue.push(Reverse((estimate, next_cost, next_id)));
+        }
+    }
+
+    None
+}
+
+/// Runs Dijkstra's algorithm from `start` to all reachable nodes and records every predecessor
+/// that lies on a cheapest path, e.g. to find all tiles on any shortest path through a maze.
+pub fn dijkstra_all<N, C, I>(start: N, mut neighbours: impl FnMut(&N) -> I) -> ShortestPaths<N, C>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Add<Output = C> + Default,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let mut nodes = Nodes::new(start);
+    let mut costs = vec![C::default()];
+    let mut predecessors: Vec<Vec<usize>> = vec![vec![]];
+    let mut closed = vec![false];
+
+    let mut queue = BinaryHeap::from([Reverse((C::default(), 0))]);
+
+    while let Some(Reverse((cost, id))) = queue.pop() {
+        if closed[id] || cost > costs[id] {
+            continue;
+        }
+        closed[id] = true;
+
+        let node = nodes.nodes[id].clone();
+        for (next, step_cost) in neighbours(&node) {
+            let next_cost = cost + step_cost;
+            let (next_id, is_new) = nodes.id(next);
+
+            if is_new {
+                costs.push(next_cost);
+                predecessors.push(vec![id]);
+                closed.push(false);
+            } else if next_cost < costs[next_id] {
+                costs[next_id] = next_cost;
+                predecessors[next_id] = vec![id];
+            } else {
+                if next_cost == costs[next_id] && !closed[next_id] {
+                    predecessors[next_id].push(id);
+                }
+                continue;
+            }
+
+            queue.push(Reverse((next_cost, next_id)));
+        }
+    }
+
+    ShortestPaths {
+        nodes,
+        costs,
+        predecessors,
+    }
+}
+
+/// The result of [`dijkstra_all`]: costs of all reachable nodes and the predecessors of each node
+/// on its cheapest paths.
+#[derive(Debug, Clone)]
+pub struct ShortestPaths<N, C> {
+    nodes: Nodes<N>,
+    costs: Vec<C>,
+    predecessors: Vec<Vec<usize>>,
+}
+
+impl<N: Clone + Eq + Hash, C: Copy> ShortestPaths<N, C> {
+    /// Cost of the cheapest path to `node`, or `None` if it is not reachable.
+    pub fn cost(&self, node: &N) -> Option<C> {
+        self.nodes.index.get(node).map(|id| self.costs[*id])
+    }
+
+    /// All reachable nodes with the cost of their cheapest path.
+    pub fn costs(&self) -> impl Iterator<Item = (&N, C)> {
+        self.nodes.nodes.iter().zip(self.costs.iter().copied())
+    }
+
+    /// All reachable nodes with the cost of their cheapest path, as a map.
+    pub fn distance_map(&self) -> FastMap<N, C> {
+        self.costs()
+            .map(|(node, cost)| (node.clone(), cost))
+            .collect()
+    }
+
+    /// The predecessors of `node` on its cheapest paths.
+    pub fn predecessors(&self, node: &N) -> impl Iterator<Item = &N> {
+        self.nodes
+            .index
+            .get(node)
+            .into_iter()
+            .flat_map(|id| self.predecessors[*id].iter())
+            .map(|id| &self.nodes.nodes[*id])
+    }
+
+    /// One of the cheapest paths from the start to `goal`.
+    pub fn path_to(&self, goal: &N) -> Option<Vec<N>> {
+        let mut path = vec![*self.nodes.index.get(goal)?];
+        while let Some(predecessor) = self.predecessors[path[path.len() - 1]].first() {
+            path.push(*predecessor);
+        }
+        Some(
+            path.into_iter()
+                .rev()
+                .map(|id| self.nodes.nodes[id].clone())
+                .collect(),
+        )
+    }
+
+    /// All nodes that lie on any cheapest path from the start to one of the `goals`.
+    pub fn nodes_on_paths<'a>(&'a self, goals: impl IntoIterator<Item = &'a N>) -> FastSet<N> {
+        let mut stack: Vec<usize> = goals
+            .into_iter()
+            .filter_map(|goal| self.nodes.index.get(goal).copied())
+            .collect();
+        let mut visited = FastSet::new();
+
+        while let Some(id) = stack.pop() {
+            if visited.insert(id) {
+                stack.extend(&self.predecessors[id]);
+            }
+        }
+
+        visited
+            .into_iter()
+            .map(|id| self.nodes.nodes[id].clone())
+            .collect()
+    }
+}
+
+/// Assigns consecutive ids to nodes, so that the searches can keep their state in vectors.
+#[derive(Debug, Clone)]
+struct Nodes<N> {
+    nodes: Vec<N>,
+    index: FastMap<N, usize>,
+}
+
+impl<N: Clone + Eq + Hash> Nodes<N> {
+    fn new(start: N) -> Self {
+        Self {
+            nodes: vec![start.clone()],
+            index: FastMap::from_iter([(start, 0)]),
+        }
+    }
+
+    /// Returns the id of `node` and whether it was seen for the first time.
+    fn id(&mut self, node: N) -> (usize, bool) {
+        if let Some(id) = self.index.get(&node) {
+            return (*id, false);
+        }
+        let id = self.nodes.len();
+        self.index.insert(node.clone(), id);
+        self.nodes.push(node);
+        (id, true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{astar, bfs, bfs_distances, dijkstra, dijkstra_all};
+    use crate::grid::Grid;
+
+    const MAZE: &str = "\
+S..#
+.#.#
+...E
+";
+
+    fn maze() -> Grid<char> {
+        Grid::parse(MAZE, |c| c).unwrap()
+    }
+
+    fn open_neighbours(grid: &Grid<char>, pos: (usize, usize)) -> Vec<(usize, usize)> {
+        grid.neighbours_4(pos).filter(|n| grid[*n] != '#').collect()
+    }
+
+    #[test]
+    fn finds_shortest_path_with_bfs() {
+        let grid = maze();
+        let path = bfs((0, 0), |p| open_neighbours(&grid, *p), |p| grid[*p] == 'E').unwrap();
+        assert_eq!(path.cost, 5);
+        assert_eq!(path.nodes.len(), 6);
+        assert_eq!(path.nodes[0], (0, 0));
+        assert_eq!(path.nodes[5], (3, 2));
+        assert!(path
+            .nodes
+            .windows(2)
+            .all(|w| w[0].0.abs_diff(w[1].0) + w[0].1.abs_diff(w[1].1) == 1));
+    }
+
+    #[test]
+    fn computes_distance_maps() {
+        let grid = maze();
+        let distances = bfs_distances((3, 2), |p| open_neighbours(&grid, *p));
+        assert_eq!(distances[&(0, 0)], 5);
+        assert_eq!(distances[&(2, 0)], 3);
+        assert!(!distances.contains_key(&(3, 0)));
+    }
+
+    #[test]
+    fn returns_none_for_unreachable_goals() {
+        let grid = maze();
+        assert!(bfs((0, 0), |p| open_neighbours(&grid, *p), |p| *p == (3, 0)).is_none());
+        assert!(dijkstra(
+            (0, 0),
+            |p| open_neighbours(&grid, *p).into_iter().map(|n| (n, 1)),
+            |p| *p == (3, 0)
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn finds_cheapest_weighted_path() {
+        // going through the direct edge is more expensive than the detour.
+        let edges = |n: &u32| match n {
+            0 => vec![(1, 1), (3, 10)],
+            1 => vec![(2, 1)],
+            2 => vec![(3, 1)],
+            _ => vec![],
+        };
+        let path = dijkstra(0, edges, |n| *n == 3).unwrap();
+        assert_eq!(path.cost, 3);
+        assert_eq!(path.nodes, vec![0, 1, 2, 3]);
+
+        let path = astar(0, edges, |n| 3 - n.min(&3), |n| *n == 3).unwrap();
+        assert_eq!(path.cost, 3);
+    }
+
+    #[test]
+    fn astar_agrees_with_dijkstra_on_grids() {
+        let grid = maze();
+        let neighbours = |p: &(usize, usize)| {
+            open_neighbours(&grid, *p)
+                .into_iter()
+                .map(|n| (n, 1usize))
+                .collect::<Vec<_>>()
+        };
+        let goal = (3, 2);
+        let manhattan = |p: &(usize, usize)| p.0.abs_diff(goal.0) + p.1.abs_diff(goal.1);
+
+        let a = astar((0, 0), neighbours, manhattan, |p| *p == goal).unwrap();
+        let d = dijkstra((0, 0), neighbours, |p| *p == goal).unwrap();
+        assert_eq!(a.cost, d.cost);
+    }
+
+    #[test]
+    fn records_all_shortest_paths() {
+        let grid = maze();
+        let paths = dijkstra_all((0, 0), |p| {
+            open_neighbours(&grid, *p)
+                .into_iter()
+                .map(|n| (n, 1))
+                .collect::<Vec<_>>()
+        });
+
+        assert_eq!(paths.cost(&(3, 2)), Some(5));
+        assert_eq!(paths.cost(&(3, 0)), None);
+        assert_eq!(paths.predecessors(&(2, 2)).count(), 2);
+
+        // both routes around the wall are shortest paths.
+        let on_paths = paths.nodes_on_paths([&(3, 2)]);
+        assert_eq!(on_paths.len(), 9);
+        assert!(!on_paths.contains(&(3, 0)));
+
+        let path = paths.path_to(&(3, 2)).unwrap();
+        assert_eq!(path.first(), Some(&(0, 0)));
+        assert_eq!(path.len(), 6);
+        assert_eq!(paths.distance_map()[&(2, 1)], 3);
+    }
+}
@@ -1,5 +1,11 @@
+use std::{process, time::Duration};
+
 use crate::template::{all_days, run_multi::run_multi};
 
-pub fn handle(is_release: bool) {
-    run_multi(&all_days().collect(), is_release, false);
+pub fn handle(is_release: bool, timeout: Option<Duration>) {
+    let run = run_multi(&all_days().collect(), is_release, None, timeout);
+
+    if run.has_failures() {
+        process::exit(1);
+    }
 }
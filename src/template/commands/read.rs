@@ -1,10 +1,16 @@
-use std::process;
+use std::{env, fs, process};
 
-use crate::template::{aoc_cli, Day};
+use crate::template::{aoc_cli, markdown, Day};
 
 pub fn handle(day: Day) {
+    // render the puzzle saved by `cargo download` without going online.
+    if let Ok(puzzle) = fs::read_to_string(aoc_cli::get_puzzle_path(day)) {
+        print!("{}", markdown::render(&puzzle, width()));
+        return;
+    }
+
     if aoc_cli::check().is_err() {
-        eprintln!("command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it.");
+        eprintln!("puzzle for day {day} has not been downloaded yet and command \"aoc\" not found or not callable. Try running \"cargo install aoc-cli\" to install it.");
         process::exit(1);
     }
 
@@ -13,3 +19,14 @@ pub fn handle(day: Day) {
         process::exit(1);
     };
 }
+
+/// Line width of the rendered puzzle: the terminal width if it is narrower than the default.
+fn width() -> usize {
+    env::var("COLUMNS")
+        .ok()
+        .and_then(|x| x.parse().ok())
+        .filter(|columns| *columns > 0)
+        .map_or(markdown::DEFAULT_WIDTH, |columns: usize| {
+            columns.min(markdown::DEFAULT_WIDTH)
+        })
+}
@@ -1,8 +1,38 @@
 use std::process::{Command, Stdio};
 
-use crate::template::Day;
+use crate::template::runner::BenchOptions;
+use crate::template::{Day, InputSource};
 
-pub fn handle(day: Day, release: bool, dhat: bool, submit_part: Option<u8>) {
+/// Options for inspecting a solution while it runs, passed to the solution binary.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DebugOptions {
+    /// Number of `--verbose` flags, see [`crate::template::trace`].
+    pub verbosity: u8,
+    /// Directory for image dumps of simulation steps, see [`crate::template::frames`].
+    pub dump_frames: Option<String>,
+}
+
+impl DebugOptions {
+    /// Arguments that enable these options when passed to a solution binary.
+    pub fn to_args(&self) -> Vec<String> {
+        let mut args = vec!["--verbose".to_string(); self.verbosity.into()];
+        if let Some(directory) = &self.dump_frames {
+            args.push("--dump-frames".into());
+            args.push(directory.clone());
+        }
+        args
+    }
+}
+
+pub fn handle(
+    day: Day,
+    release: bool,
+    dhat: bool,
+    submit_part: Option<u8>,
+    input: &InputSource,
+    bench: Option<BenchOptions>,
+    debug: &DebugOptions,
+) {
     let mut cmd_args = vec!["run".to_string(), "--bin".to_string(), day.to_string()];
 
     if dhat {
@@ -23,6 +53,14 @@ pub fn handle(day: Day, release: bool, dhat: bool, submit_part: Option<u8>) {
         cmd_args.push(submit_part.to_string());
     }
 
+    if let Some(bench) = bench {
+        cmd_args.extend(bench.to_args());
+    }
+
+    cmd_args.extend(input.to_args());
+
+    cmd_args.extend(debug.to_args());
+
     let mut cmd = Command::new("cargo")
         .args(&cmd_args)
         .stdout(Stdio::inherit())
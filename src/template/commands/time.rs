@@ -1,10 +1,19 @@
 use std::collections::HashSet;
+use std::process::{self, Command, Stdio};
+use std::time::Duration;
 
 use crate::template::run_multi::run_multi;
+use crate::template::runner::BenchOptions;
 use crate::template::timings::Timings;
 use crate::template::{all_days, readme_benchmarks, Day};
 
-pub fn handle(day: Option<Day>, run_all: bool, store: bool) {
+pub fn handle(
+    day: Option<Day>,
+    run_all: bool,
+    store: bool,
+    bench: BenchOptions,
+    timeout: Option<Duration>,
+) {
     let stored_timings = Timings::read_from_file();
 
     let days_to_run = day.map_or_else(
@@ -21,10 +30,10 @@ pub fn handle(day: Option<Day>, run_all: bool, store: bool) {
         |day| HashSet::from([day]),
     );
 
-    let timings = run_multi(&days_to_run, true, true).unwrap();
+    let run = run_multi(&days_to_run, true, Some(bench), timeout);
 
     if store {
-        let merged_timings = stored_timings.merge(&timings);
+        let merged_timings = stored_timings.merge(run.timings.as_ref().unwrap());
         merged_timings.store_file().unwrap();
 
         println!();
@@ -37,4 +46,33 @@ pub fn handle(day: Option<Day>, run_all: bool, store: bool) {
             }
         }
     }
+
+    if run.has_failures() {
+        process::exit(1);
+    }
+}
+
+/// Bench all registered variants of a day's parts against each other.
+pub fn handle_variants(day: Day, bench: BenchOptions) {
+    let day_padded = day.to_string();
+
+    let status = Command::new("cargo")
+        .args([
+            "run",
+            "--quiet",
+            "--release",
+            "--bin",
+            &day_padded,
+            "--",
+            "--variants",
+        ])
+        .args(bench.to_args())
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .status()
+        .unwrap();
+
+    if !status.success() {
+        process::exit(status.code().unwrap_or(1));
+    }
 }
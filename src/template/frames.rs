@@ -0,0 +1,54 @@
+//! Image dumps of the states of a simulation, e.g. to find a picture among thousands of steps.
+//!
+//! Frames are only written if the binary was invoked with `--dump-frames <dir>`, and not while
+//! benching. Each frame is a PNG file named after the simulation and its step.
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+use crate::image::Image;
+use crate::template::trace;
+
+/// The directory passed with `--dump-frames`, created on first use.
+fn directory() -> Option<&'static PathBuf> {
+    static DIRECTORY: OnceLock<Option<PathBuf>> = OnceLock::new();
+    DIRECTORY
+        .get_or_init(|| {
+            let args: Vec<String> = env::args().collect();
+            let index = args.iter().position(|x| x == "--dump-frames")?;
+            let directory = PathBuf::from(args.get(index + 1)?);
+            fs::create_dir_all(&directory).unwrap_or_else(|e| {
+                panic!("could not create {}: {e}", directory.display());
+            });
+            Some(directory)
+        })
+        .as_ref()
+}
+
+/// Returns `true` if frames are currently written.
+pub fn enabled() -> bool {
+    !trace::is_silenced() && directory().is_some()
+}
+
+/// Writes the image returned by `frame` to `<dir>/<name>-<step>.png`. `frame` is only called if
+/// frames are enabled, so it may be expensive.
+///
+/// ```no_run
+/// # use advent_of_code::{grid::Grid, image::{Image, Rgb}};
+/// # let grid = Grid::new(3, 3, false);
+/// advent_of_code::template::frames::dump("part1", 42, || {
+///     Image::from_grid(&grid, |&wall| if wall { Rgb::WHITE } else { Rgb::BLACK })
+/// });
+/// ```
+pub fn dump(name: &str, step: usize, frame: impl FnOnce() -> Image) {
+    if !enabled() {
+        return;
+    }
+
+    let path = directory().unwrap().join(format!("{name}-{step:05}.png"));
+    if let Err(e) = frame().save(&path) {
+        panic!("could not write frame {}: {e}", path.display());
+    }
+}
@@ -0,0 +1,123 @@
+use std::fmt::Display;
+use std::io::{self, Read};
+use std::sync::OnceLock;
+use std::{env, fs, process};
+
+use crate::template::{normalize_input, Day};
+
+/// The source a solution binary reads its puzzle input from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSource {
+    /// The real puzzle input in `data/inputs`.
+    Input,
+    /// An example in `data/examples`. If a part is given, reads the suffixed file, e.g. `01-2.txt`.
+    Example(Option<u8>),
+    /// A file passed via `--input <path>`.
+    Path(String),
+    /// Stdin, selected via `--input -`.
+    Stdin,
+}
+
+impl InputSource {
+    /// Creates an [`InputSource`] from the value of an `--input` argument.
+    pub fn from_path(path: String) -> Self {
+        if path == "-" {
+            Self::Stdin
+        } else {
+            Self::Path(path)
+        }
+    }
+
+    /// Reads the input source from the arguments of the current process.
+    pub fn from_args() -> Self {
+        let args: Vec<String> = env::args().collect();
+
+        if let Some(index) = args.iter().position(|x| x == "--input") {
+            let Some(path) = args.get(index + 1) else {
+                eprintln!("Unexpected command-line input. Format: cargo solve 1 --input <path>");
+                process::exit(1);
+            };
+            return Self::from_path(path.clone());
+        }
+
+        if let Some(index) = args.iter().position(|x| x == "--example") {
+            let part = args.get(index + 1).and_then(|x| x.parse().ok());
+            return Self::Example(part);
+        }
+
+        Self::Input
+    }
+
+    /// Arguments that select this source when passed to a solution binary.
+    pub fn to_args(&self) -> Vec<String> {
+        match self {
+            Self::Input => vec![],
+            Self::Example(None) => vec!["--example".into()],
+            Self::Example(Some(part)) => vec!["--example".into(), part.to_string()],
+            Self::Path(path) => vec!["--input".into(), path.clone()],
+            Self::Stdin => vec!["--input".into(), "-".into()],
+        }
+    }
+
+    /// Reads the input for `day` from this source and normalizes it (see [`normalize_input`]).
+    pub fn read(&self, day: Day) -> Result<String, String> {
+        let input = match self {
+            Self::Input => read_path(&format!("data/inputs/{day}.txt"))
+                .map_err(|e| format!("{e}. Try running \"cargo download {day}\" to download it.")),
+            Self::Example(None) => read_path(&format!("data/examples/{day}.txt")),
+            Self::Example(Some(part)) => read_path(&format!("data/examples/{day}-{part}.txt")),
+            Self::Path(path) => read_path(path),
+            Self::Stdin => {
+                let mut input = String::new();
+                io::stdin()
+                    .read_to_string(&mut input)
+                    .map_err(|e| format!("could not read input from stdin: {e}"))?;
+                Ok(input)
+            }
+        }?;
+
+        Ok(normalize_input(&input))
+    }
+}
+
+impl Display for InputSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Input => write!(f, "input"),
+            Self::Example(None) => write!(f, "example"),
+            Self::Example(Some(part)) => write!(f, "example {part}"),
+            Self::Path(path) => write!(f, "{path}"),
+            Self::Stdin => write!(f, "stdin"),
+        }
+    }
+}
+
+fn read_path(path: &str) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|e| format!("could not open input file \"{path}\": {e}"))
+}
+
+/// Returns the input source the current solution binary was invoked with.
+pub fn input_source() -> &'static InputSource {
+    static SOURCE: OnceLock<InputSource> = OnceLock::new();
+    SOURCE.get_or_init(InputSource::from_args)
+}
+
+/// Returns `true` if the current solution binary runs against an example file.
+///
+/// Use this for days where the example is solved with different parameters than the real input,
+/// e.g. a smaller grid size.
+pub fn is_example() -> bool {
+    matches!(input_source(), InputSource::Example(_))
+}
+
+/// Helper function that reads the puzzle input of a solution binary to a string.
+///
+/// The source is selected via `--input <path>` (`-` for stdin) or `--example [part]`,
+/// and defaults to `data/inputs/{day}.txt`.
+#[must_use]
+pub fn read_input(day: Day) -> String {
+    input_source().read(day).unwrap_or_else(|e| {
+        eprintln!("{e}");
+        process::exit(1);
+    })
+}
@@ -0,0 +1,278 @@
+//! Renders the puzzle descriptions written by aoc-cli (`data/puzzles/DD.md`) for the terminal.
+//!
+//! Only the subset of markdown that aoc-cli produces is supported: setext and atx headings,
+//! paragraphs, list items, fenced code blocks, `*emphasis*`, `` `code` ``, links and escapes.
+//! Emphasis (which marks key phrases on the website) is rendered bold, inline code italic and
+//! the answers of solved parts bold and italic.
+
+use crate::template::{ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
+
+/// Default line width. Narrow enough to be comfortable to read and to pipe into a pager.
+pub const DEFAULT_WIDTH: usize = 80;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Style {
+    bold: bool,
+    italic: bool,
+}
+
+impl Style {
+    fn ansi(self) -> String {
+        let mut codes = String::new();
+        if self.bold {
+            codes.push_str(ANSI_BOLD);
+        }
+        if self.italic {
+            codes.push_str(ANSI_ITALIC);
+        }
+        codes
+    }
+}
+
+type StyledChar = (char, Style);
+
+/// Renders `markdown`, wrapping paragraphs at `width` characters.
+#[must_use]
+pub fn render(markdown: &str, width: usize) -> String {
+    let mut out = String::new();
+    let mut paragraph: Vec<&str> = vec![];
+    let mut lines = markdown.lines().peekable();
+
+    while let Some(line) = lines.next() {
+        let trimmed = line.trim();
+
+        if trimmed.starts_with("```") {
+            flush_paragraph(&mut out, &mut paragraph, width);
+            for code_line in lines.by_ref() {
+                if code_line.trim().starts_with("```") {
+                    break;
+                }
+                out.push_str(&format!("    {code_line}\n"));
+            }
+            out.push('\n');
+        } else if trimmed.is_empty() {
+            flush_paragraph(&mut out, &mut paragraph, width);
+            // separates lists from the following block.
+            if !out.is_empty() && !out.ends_with("\n\n") {
+                out.push('\n');
+            }
+        } else if let Some(heading) = atx_heading(trimmed) {
+            flush_paragraph(&mut out, &mut paragraph, width);
+            push_heading(&mut out, heading, width);
+        } else if paragraph.is_empty() && lines.peek().is_some_and(|next| is_underline(next)) {
+            lines.next();
+            push_heading(&mut out, trimmed, width);
+        } else if let Some(item) = list_item(trimmed) {
+            flush_paragraph(&mut out, &mut paragraph, width);
+            push_wrapped(&mut out, &inline(item), width, "  • ", "    ");
+        } else {
+            paragraph.push(trimmed);
+        }
+    }
+
+    flush_paragraph(&mut out, &mut paragraph, width);
+
+    let len = out.trim_end().len();
+    out.truncate(len);
+    out.push('\n');
+    out
+}
+
+fn atx_heading(line: &str) -> Option<&str> {
+    let heading = line.trim_start_matches('#');
+    (heading.len() < line.len() && heading.starts_with(' ')).then(|| heading.trim())
+}
+
+fn is_underline(line: &str) -> bool {
+    let line = line.trim();
+    !line.is_empty() && (line.chars().all(|c| c == '-') || line.chars().all(|c| c == '='))
+}
+
+fn list_item(line: &str) -> Option<&str> {
+    line.strip_prefix("* ").or_else(|| line.strip_prefix("- "))
+}
+
+fn push_heading(out: &mut String, heading: &str, width: usize) {
+    let styled: Vec<StyledChar> = inline(heading)
+        .into_iter()
+        .map(|(c, style)| {
+            (
+                c,
+                Style {
+                    bold: true,
+                    ..style
+                },
+            )
+        })
+        .collect();
+    push_wrapped(out, &styled, width, "", "");
+    out.push('\n');
+}
+
+fn flush_paragraph(out: &mut String, paragraph: &mut Vec<&str>, width: usize) {
+    if paragraph.is_empty() {
+        return;
+    }
+    let text = paragraph.join(" ");
+    let mut styled = inline(&text);
+
+    // answers of solved parts are emphasized, like on the website.
+    if text.starts_with("Your puzzle answer was") {
+        for (_, style) in styled.iter_mut().filter(|(_, style)| style.italic) {
+            style.bold = true;
+        }
+    }
+
+    push_wrapped(out, &styled, width, "", "");
+    out.push('\n');
+    paragraph.clear();
+}
+
+/// Resolves inline markup of `text` into styled characters.
+fn inline(text: &str) -> Vec<StyledChar> {
+    let mut styled = vec![];
+    let mut style = Style::default();
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                if let Some(escaped) = chars.next() {
+                    styled.push((escaped, style));
+                }
+            }
+            '`' => style.italic = !style.italic,
+            '*' => style.bold = !style.bold,
+            // link texts are kept, targets are dropped.
+            '[' if !style.italic => {}
+            ']' if !style.italic && chars.peek() == Some(&'(') => {
+                for target in chars.by_ref() {
+                    if target == ')' {
+                        break;
+                    }
+                }
+            }
+            c => styled.push((c, style)),
+        }
+    }
+
+    styled
+}
+
+/// Word-wraps `text` at `width` visible characters. The first line is prefixed with `first`,
+/// continuation lines with `rest`.
+fn push_wrapped(out: &mut String, text: &[StyledChar], width: usize, first: &str, rest: &str) {
+    let mut line: Vec<StyledChar> = vec![];
+    let mut prefix = first;
+
+    for word in text.split(|(c, _)| *c == ' ').filter(|w| !w.is_empty()) {
+        let needed = if line.is_empty() { 0 } else { line.len() + 1 };
+        if !line.is_empty() && prefix.chars().count() + needed + word.len() > width {
+            push_line(out, prefix, &line);
+            line.clear();
+            prefix = rest;
+        }
+        if !line.is_empty() {
+            // spaces between styled words keep the style, e.g. for multi-word emphasis.
+            let previous = line.last().map(|(_, s)| *s).unwrap_or_default();
+            let space = if previous == word[0].1 {
+                previous
+            } else {
+                Style::default()
+            };
+            line.push((' ', space));
+        }
+        line.extend_from_slice(word);
+    }
+
+    if !line.is_empty() {
+        push_line(out, prefix, &line);
+    }
+}
+
+fn push_line(out: &mut String, prefix: &str, line: &[StyledChar]) {
+    out.push_str(prefix);
+
+    let mut current = Style::default();
+    for (c, style) in line {
+        if *style != current {
+            if current != Style::default() {
+                out.push_str(ANSI_RESET);
+            }
+            out.push_str(&style.ansi());
+            current = *style;
+        }
+        out.push(*c);
+    }
+
+    if current != Style::default() {
+        out.push_str(ANSI_RESET);
+    }
+    out.push('\n');
+}
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::render;
+    use crate::template::{ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
+
+    fn strip_ansi(s: &str) -> String {
+        [ANSI_BOLD, ANSI_ITALIC, ANSI_RESET]
+            .iter()
+            .fold(s.to_string(), |s, code| s.replace(code, ""))
+    }
+
+    #[test]
+    fn renders_headings_and_escapes() {
+        let rendered = render("\\--- Day 1: Test ---\n----------\n\nSome text.\n", 80);
+        assert_eq!(
+            rendered,
+            format!("{ANSI_BOLD}--- Day 1: Test ---{ANSI_RESET}\n\nSome text.\n")
+        );
+    }
+
+    #[test]
+    fn renders_emphasis_in_code() {
+        let rendered = render("Your puzzle answer was `*42*`.", 80);
+        assert_eq!(
+            rendered,
+            format!("Your puzzle answer was {ANSI_BOLD}{ANSI_ITALIC}42{ANSI_RESET}.\n")
+        );
+    }
+
+    #[test]
+    fn emphasizes_solved_answers() {
+        let rendered = render("Your puzzle answer was `42`.", 80);
+        assert_eq!(
+            rendered,
+            format!("Your puzzle answer was {ANSI_BOLD}{ANSI_ITALIC}42{ANSI_RESET}.\n")
+        );
+    }
+
+    #[test]
+    fn keeps_code_blocks_verbatim() {
+        let rendered = render("For example:\n\n```\n3   4\n*  *\n```\n\nDone.", 10);
+        assert_eq!(rendered, "For\nexample:\n\n    3   4\n    *  *\n\nDone.\n");
+    }
+
+    #[test]
+    fn wraps_paragraphs_and_drops_link_targets() {
+        let rendered = render(
+            "A [very long](https://example.com) paragraph\nthat spans lines.",
+            20,
+        );
+        assert_eq!(
+            strip_ansi(&rendered),
+            "A very long\nparagraph that spans\nlines.\n"
+        );
+    }
+
+    #[test]
+    fn indents_list_items() {
+        let rendered = render("* one two three four\n* five\n\nsix", 12);
+        assert_eq!(
+            rendered,
+            "  • one two\n    three\n    four\n  • five\n\nsix\n"
+        );
+    }
+}
@@ -2,11 +2,16 @@ use std::{env, fs};
 
 pub mod aoc_cli;
 pub mod commands;
+pub mod frames;
 pub mod runner;
+pub mod trace;
 
 pub use day::*;
+pub use input::*;
 
 mod day;
+mod input;
+mod markdown;
 mod readme_benchmarks;
 mod run_multi;
 mod timings;
@@ -15,16 +20,30 @@ pub const ANSI_ITALIC: &str = "\x1b[3m";
 pub const ANSI_BOLD: &str = "\x1b[1m";
 pub const ANSI_RESET: &str = "\x1b[0m";
 
-/// Helper function that reads a text file to a string.
+/// Normalizes puzzle input: converts CRLF line endings to LF and makes sure that
+/// non-empty input ends with exactly one trailing newline.
+#[must_use]
+pub fn normalize_input(input: &str) -> String {
+    let mut normalized = input.replace("\r\n", "\n");
+    normalized.truncate(normalized.trim_end_matches('\n').len());
+
+    if !normalized.is_empty() {
+        normalized.push('\n');
+    }
+
+    normalized
+}
+
+/// Helper function that reads a text file to a normalized string (see [`normalize_input`]).
 #[must_use]
 pub fn read_file(folder: &str, day: Day) -> String {
     let cwd = env::current_dir().unwrap();
     let filepath = cwd.join("data").join(folder).join(format!("{day}.txt"));
     let f = fs::read_to_string(filepath);
-    f.expect("could not open input file")
+    normalize_input(&f.expect("could not open input file"))
 }
 
-/// Helper function that reads a text file to string, appending a part suffix. E.g. like `01-2.txt`.
+/// Helper function that reads a text file to a normalized string, appending a part suffix. E.g. like `01-2.txt`.
 #[must_use]
 pub fn read_file_part(folder: &str, day: Day, part: u8) -> String {
     let cwd = env::current_dir().unwrap();
@@ -33,25 +52,38 @@ pub fn read_file_part(folder: &str, day: Day, part: u8) -> String {
         .join(folder)
         .join(format!("{day}-{part}.txt"));
     let f = fs::read_to_string(filepath);
-    f.expect("could not open input file")
+    normalize_input(&f.expect("could not open input file"))
 }
 
 /// Creates the constant `DAY` and sets up the input and runner for each part.
 ///
 /// The optional, second parameter (1 or 2) allows you to only run a single part of the solution.
+///
+/// Alternative implementations of a part can be registered with `variants: [name: part, ...]`,
+/// e.g. `solution!(2, variants: [part_two_brute_force: 2])`. They are benched against the
+/// main implementation with `cargo time <day> --variants`.
 #[macro_export]
 macro_rules! solution {
     ($day:expr) => {
-        $crate::solution!(@impl $day, [part_one, 1] [part_two, 2]);
+        $crate::solution!(@impl $day, [part_one, 1] [part_two, 2], []);
     };
     ($day:expr, 1) => {
-        $crate::solution!(@impl $day, [part_one, 1]);
+        $crate::solution!(@impl $day, [part_one, 1], []);
     };
     ($day:expr, 2) => {
-        $crate::solution!(@impl $day, [part_two, 2]);
+        $crate::solution!(@impl $day, [part_two, 2], []);
+    };
+    ($day:expr, variants: [$( $variant:ident: $variant_part:literal ),* $(,)?]) => {
+        $crate::solution!(@impl $day, [part_one, 1] [part_two, 2], [$( [$variant, $variant_part] )*]);
+    };
+    ($day:expr, 1, variants: [$( $variant:ident: $variant_part:literal ),* $(,)?]) => {
+        $crate::solution!(@impl $day, [part_one, 1], [$( [$variant, $variant_part] )*]);
+    };
+    ($day:expr, 2, variants: [$( $variant:ident: $variant_part:literal ),* $(,)?]) => {
+        $crate::solution!(@impl $day, [part_two, 2], [$( [$variant, $variant_part] )*]);
     };
 
-    (@impl $day:expr, $( [$func:expr, $part:expr] )*) => {
+    (@impl $day:expr, $( [$func:expr, $part:expr] )*, [$( [$variant:expr, $variant_part:expr] )*]) => {
         /// The current day.
         const DAY: $crate::template::Day = $crate::day!($day);
 
@@ -61,8 +93,41 @@ macro_rules! solution {
 
         fn main() {
             use $crate::template::runner::*;
-            let input = $crate::template::read_file("inputs", DAY);
-            $( run_part($func, &input, DAY, $part); )*
+            let input = $crate::template::read_input(DAY);
+
+            if std::env::args().any(|x| x == "--variants") {
+                let runs = vec![
+                    $( run_variant(stringify!($func), $part, $func, &input), )*
+                    $( run_variant(stringify!($variant), $variant_part, $variant, &input), )*
+                ];
+                if !print_variants(&runs) {
+                    std::process::exit(1);
+                }
+                return;
+            }
+
+            let mut success = true;
+            $( success &= run_part($func, &input, DAY, $part); )*
+            if !success {
+                std::process::exit(1);
+            }
         }
     };
 }
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use super::normalize_input;
+
+    #[test]
+    fn normalizes_line_endings() {
+        assert_eq!(normalize_input("a\r\nb\r\n"), "a\nb\n");
+    }
+
+    #[test]
+    fn normalizes_trailing_newlines() {
+        assert_eq!(normalize_input("a\nb"), "a\nb\n");
+        assert_eq!(normalize_input("a\n\nb\n\n\n"), "a\n\nb\n");
+        assert_eq!(normalize_input(""), "");
+    }
+}
@@ -1,5 +1,6 @@
-use std::{collections::HashSet, io};
+use std::{collections::HashSet, fmt::Display, io, time::Duration};
 
+use crate::template::runner::BenchOptions;
 use crate::template::{Day, ANSI_BOLD, ANSI_ITALIC, ANSI_RESET};
 
 use super::{
@@ -7,8 +8,102 @@ use super::{
     timings::{Timing, Timings},
 };
 
-pub fn run_multi(days_to_run: &HashSet<Day>, is_release: bool, is_timed: bool) -> Option<Timings> {
+/// Outcome of running the solution binary of a single day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunStatus {
+    /// The binary exited successfully.
+    Solved,
+    /// The binary exited with a non-zero status without panicking, e.g. because it failed to compile.
+    Failed(Option<i32>),
+    /// The solution panicked.
+    Panicked,
+    /// The binary was killed after exceeding the timeout.
+    TimedOut,
+}
+
+impl RunStatus {
+    /// Classifies the result of a finished (or killed) child process.
+    pub fn classify(
+        timed_out: bool,
+        success: bool,
+        exit_code: Option<i32>,
+        panicked: bool,
+    ) -> Self {
+        if timed_out {
+            RunStatus::TimedOut
+        } else if success {
+            RunStatus::Solved
+        } else if panicked {
+            RunStatus::Panicked
+        } else {
+            RunStatus::Failed(exit_code)
+        }
+    }
+
+    /// Category of the status in the run summary.
+    fn label(self) -> &'static str {
+        match self {
+            RunStatus::Solved => "Solved",
+            RunStatus::Failed(_) => "Failed",
+            RunStatus::Panicked => "Panicked",
+            RunStatus::TimedOut => "Timed out",
+        }
+    }
+}
+
+impl Display for RunStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RunStatus::Solved => write!(f, "solved"),
+            RunStatus::Failed(Some(code)) => write!(f, "failed (exit code {code})"),
+            RunStatus::Failed(None) => write!(f, "failed"),
+            RunStatus::Panicked => write!(f, "panicked"),
+            RunStatus::TimedOut => write!(f, "timed out"),
+        }
+    }
+}
+
+/// Result of running a set of days.
+pub struct MultiRun {
+    pub timings: Option<Timings>,
+    pub statuses: Vec<(Day, RunStatus)>,
+}
+
+impl MultiRun {
+    /// Returns `true` if any day did not exit successfully.
+    pub fn has_failures(&self) -> bool {
+        self.statuses
+            .iter()
+            .any(|(_, status)| *status != RunStatus::Solved)
+    }
+
+    fn print_summary(&self) {
+        println!("\n{ANSI_BOLD}Summary{ANSI_RESET}");
+        println!("-------");
+
+        for label in ["Solved", "Failed", "Panicked", "Timed out"] {
+            let days: Vec<String> = self
+                .statuses
+                .iter()
+                .filter(|(_, status)| status.label() == label)
+                .map(|(day, _)| day.to_string())
+                .collect();
+
+            if !days.is_empty() {
+                println!("{label}: {}", days.join(", "));
+            }
+        }
+    }
+}
+
+pub fn run_multi(
+    days_to_run: &HashSet<Day>,
+    is_release: bool,
+    bench: Option<BenchOptions>,
+    timeout: Option<Duration>,
+) -> MultiRun {
     let mut timings: Vec<Timing> = Vec::with_capacity(days_to_run.len());
+    let mut statuses: Vec<(Day, RunStatus)> = Vec::with_capacity(days_to_run.len());
 
     let mut need_space = false;
 
@@ -24,17 +119,30 @@ pub fn run_multi(days_to_run: &HashSet<Day>, is_release: bool, is_timed: bool) -
             println!("{ANSI_BOLD}Day {day}{ANSI_RESET}");
             println!("------");
 
-            let output = child_commands::run_solution(day, is_timed, is_release).unwrap();
-
-            if output.is_empty() {
+            let Some(run) = child_commands::run_solution(day, bench, is_release, timeout).unwrap()
+            else {
                 println!("Not solved.");
-            } else {
-                let val = child_commands::parse_exec_time(&output, day);
-                timings.push(val);
+                return;
+            };
+
+            match run.status {
+                RunStatus::Solved => {
+                    if run.output.is_empty() {
+                        println!("Not solved.");
+                    } else {
+                        timings.push(child_commands::parse_exec_time(&run.output, day));
+                    }
+                }
+                status => {
+                    // partial output of a crashed run does not contain reliable timings.
+                    eprintln!("Day {day} {status}.");
+                }
             }
+
+            statuses.push((day, run.status));
         });
 
-    if is_timed {
+    let timings = if bench.is_some() {
         let timings = Timings { data: timings };
         let total_millis = timings.total_millis();
         println!(
@@ -43,7 +151,11 @@ pub fn run_multi(days_to_run: &HashSet<Day>, is_release: bool, is_timed: bool) -
         Some(timings)
     } else {
         None
-    }
+    };
+
+    let run = MultiRun { timings, statuses };
+    run.print_summary();
+    run
 }
 
 #[allow(dead_code)]
@@ -67,20 +179,33 @@ pub fn get_path_for_bin(day: Day) -> String {
 /// All solutions live in isolated binaries.
 /// This module encapsulates interaction with these binaries, both invoking them as well as parsing the timing output.
 pub mod child_commands {
-    use super::{get_path_for_bin, Error};
-    use crate::template::Day;
+    use super::{get_path_for_bin, BenchOptions, Error, RunStatus};
+    use crate::template::{runner, Day};
     use std::{
         io::{BufRead, BufReader},
         path::Path,
         process::{Command, Stdio},
         thread,
+        time::{Duration, Instant},
     };
 
-    /// Run the solution bin for a given day
-    pub fn run_solution(day: Day, is_timed: bool, is_release: bool) -> Result<Vec<String>, Error> {
+    /// Output and exit status of a solution binary.
+    pub struct SolutionRun {
+        pub output: Vec<String>,
+        pub status: RunStatus,
+    }
+
+    /// Run the solution bin for a given day.
+    /// Returns `None` for days that have not been scaffolded yet.
+    pub fn run_solution(
+        day: Day,
+        bench: Option<BenchOptions>,
+        is_release: bool,
+        timeout: Option<Duration>,
+    ) -> Result<Option<SolutionRun>, Error> {
         // skip command invocation for days that have not been scaffolded yet.
         if !Path::new(&get_path_for_bin(day)).exists() {
-            return Ok(vec![]);
+            return Ok(None);
         }
 
         let day_padded = day.to_string();
@@ -90,10 +215,26 @@ pub mod child_commands {
             args.push("--release");
         }
 
-        if is_timed {
-            // mirror `--time` flag to child invocations.
+        let bench_args = bench.map(BenchOptions::to_args).unwrap_or_default();
+
+        if timeout.is_some() {
+            // build ahead of time so that compilation does not count towards the timeout.
+            let mut build_args = args.clone();
+            build_args[0] = "build";
+
+            let status = Command::new("cargo").args(&build_args).status()?;
+            if !status.success() {
+                return Ok(Some(SolutionRun {
+                    output: vec![],
+                    status: RunStatus::Failed(status.code()),
+                }));
+            }
+        }
+
+        if !bench_args.is_empty() {
+            // mirror `--time` flags to child invocations.
             args.push("--");
-            args.push("--time");
+            args.extend(bench_args.iter().map(String::as_str));
         }
 
         // spawn child command with piped stdout/stderr.
@@ -108,24 +249,61 @@ pub mod child_commands {
         let stdout = BufReader::new(cmd.stdout.take().ok_or(super::Error::BrokenPipe)?);
         let stderr = BufReader::new(cmd.stderr.take().ok_or(super::Error::BrokenPipe)?);
 
-        let mut output = vec![];
-
-        let thread = thread::spawn(move || {
-            stderr.lines().for_each(|line| {
-                eprintln!("{}", line.unwrap());
+        let stderr_thread = thread::spawn(move || {
+            let mut panicked = false;
+            stderr.lines().map_while(Result::ok).for_each(|line| {
+                panicked |= line.contains("panicked at");
+                eprintln!("{line}");
             });
+            panicked
         });
 
-        for line in stdout.lines() {
-            let line = line.unwrap();
-            println!("{line}");
-            output.push(line);
-        }
+        let stdout_thread = thread::spawn(move || {
+            let mut output = vec![];
+            for line in stdout.lines().map_while(Result::ok) {
+                println!("{line}");
+                output.push(line);
+            }
+            output
+        });
 
-        thread.join().unwrap();
-        cmd.wait()?;
+        let mut timed_out = false;
+
+        let status = match timeout {
+            Some(timeout) => {
+                let timer = Instant::now();
+                loop {
+                    if let Some(status) = cmd.try_wait()? {
+                        break status;
+                    }
+                    if timer.elapsed() > timeout {
+                        // `cargo run` replaces itself with the solution binary on unix,
+                        // so this terminates the solution as well.
+                        cmd.kill()?;
+                        timed_out = true;
+                        break cmd.wait()?;
+                    }
+                    thread::sleep(Duration::from_millis(10));
+                }
+            }
+            None => cmd.wait()?,
+        };
+
+        let output = stdout_thread.join().unwrap();
+        let panicked = stderr_thread.join().unwrap()
+            || output
+                .iter()
+                .any(|line| line.contains(runner::PANIC_MARKER));
+
+        if timed_out {
+            println!();
+            eprintln!("Timed out after {:.1?}.", timeout.unwrap_or_default());
+        }
 
-        Ok(output)
+        Ok(Some(SolutionRun {
+            output,
+            status: RunStatus::classify(timed_out, status.success(), status.code(), panicked),
+        }))
     }
 
     pub fn parse_exec_time(output: &[String], day: Day) -> super::Timing {
@@ -174,7 +352,7 @@ pub mod child_commands {
             .split(" samples)")
             .next()?
             .split('(')
-            .last()?
+            .next_back()?
             .split('@')
             .next()?
             .trim();
@@ -208,6 +386,31 @@ pub mod child_commands {
         use super::parse_exec_time;
 
         use crate::day;
+        use crate::template::run_multi::RunStatus;
+
+        #[test]
+        fn classifies_run_status() {
+            assert_eq!(
+                RunStatus::classify(false, true, Some(0), false),
+                RunStatus::Solved
+            );
+            assert_eq!(
+                RunStatus::classify(false, false, Some(101), true),
+                RunStatus::Panicked
+            );
+            assert_eq!(
+                RunStatus::classify(false, false, Some(101), false),
+                RunStatus::Failed(Some(101))
+            );
+            assert_eq!(
+                RunStatus::classify(true, false, None, false),
+                RunStatus::TimedOut
+            );
+            assert_eq!(
+                RunStatus::classify(true, false, None, true),
+                RunStatus::TimedOut
+            );
+        }
 
         #[test]
         fn parses_execution_times() {
@@ -224,6 +427,26 @@ pub mod child_commands {
             assert_eq!(res.part_2.unwrap(), "74.13ms");
         }
 
+        #[test]
+        fn parses_execution_times_with_cold_start() {
+            let res = parse_exec_time(
+                &["Part 1: 42 (1.5ms @ 650 samples) (cold: 3.2ms)".into()],
+                day!(1),
+            );
+            assert_approx_eq!(res.total_nanos, 1500000_f64);
+            assert_eq!(res.part_1.unwrap(), "1.5ms");
+        }
+
+        #[test]
+        fn parses_execution_times_with_threads() {
+            let res = parse_exec_time(
+                &["Part 2: 42 (1.5ms @ 650 samples) (8 threads) (cold: 3.2ms)".into()],
+                day!(1),
+            );
+            assert_approx_eq!(res.total_nanos, 1500000_f64);
+            assert_eq!(res.part_2.unwrap(), "1.5ms");
+        }
+
         #[test]
         fn parses_with_patterns_in_input() {
             let res = parse_exec_time(
@@ -2,62 +2,387 @@
 use std::fmt::Display;
 use std::hint::black_box;
 use std::io::{stdout, Write};
+use std::panic::{self, AssertUnwindSafe};
 use std::process::Output;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, Once};
 use std::time::{Duration, Instant};
 use std::{cmp, env, process};
 
+use crate::parallel;
 use crate::template::ANSI_BOLD;
-use crate::template::{aoc_cli, Day, ANSI_ITALIC, ANSI_RESET};
+use crate::template::{aoc_cli, trace, Day, ANSI_ITALIC, ANSI_RESET};
 
-pub fn run_part<I: Copy, T: Display>(func: impl Fn(I) -> Option<T>, input: I, day: Day, part: u8) {
+/// Outcome of running a solution part once.
+#[derive(Debug, PartialEq, Eq)]
+pub enum PartOutcome<T> {
+    Solved(T),
+    Unsolved,
+    Error(String),
+    Panicked(String),
+}
+
+impl<T> PartOutcome<T> {
+    /// Returns `true` if the part errored or panicked.
+    pub fn is_failure(&self) -> bool {
+        matches!(self, PartOutcome::Error(_) | PartOutcome::Panicked(_))
+    }
+}
+
+/// Return types that are accepted for solution parts.
+pub trait PartResult {
+    type Output: Display;
+
+    fn into_outcome(self) -> PartOutcome<Self::Output>;
+}
+
+impl<T: Display> PartResult for Option<T> {
+    type Output = T;
+
+    fn into_outcome(self) -> PartOutcome<T> {
+        match self {
+            Some(result) => PartOutcome::Solved(result),
+            None => PartOutcome::Unsolved,
+        }
+    }
+}
+
+impl<T: Display, E: Display> PartResult for Result<T, E> {
+    type Output = T;
+
+    fn into_outcome(self) -> PartOutcome<T> {
+        match self {
+            Ok(result) => PartOutcome::Solved(result),
+            Err(e) => PartOutcome::Error(e.to_string()),
+        }
+    }
+}
+
+/// Options for benching solution parts, passed to solution binaries as command-line flags.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BenchOptions {
+    /// Report the time of the first (cold) execution next to the benched average.
+    pub cold: bool,
+    /// Discard samples outside of the Tukey fences (1.5 IQR) before averaging.
+    pub reject_outliers: bool,
+}
+
+impl BenchOptions {
+    /// Reads bench options from the arguments of the current process.
+    /// Returns `None` if the part should not be benched (no `--time` flag).
+    pub fn from_args() -> Option<Self> {
+        let args: Vec<String> = env::args().collect();
+
+        if !args.iter().any(|x| x == "--time") {
+            return None;
+        }
+
+        Some(Self {
+            cold: args.iter().any(|x| x == "--cold"),
+            reject_outliers: args.iter().any(|x| x == "--reject-outliers"),
+        })
+    }
+
+    /// Arguments that enable benching with these options when passed to a solution binary.
+    pub fn to_args(self) -> Vec<String> {
+        let mut args = vec!["--time".to_string()];
+        if self.cold {
+            args.push("--cold".into());
+        }
+        if self.reject_outliers {
+            args.push("--reject-outliers".into());
+        }
+        args
+    }
+}
+
+/// Printed for parts that panicked. `run_multi` looks for this marker to classify a day as panicked.
+pub const PANIC_MARKER: &str = "✖ panicked:";
+
+/// Run a solution part and print its result.
+/// Returns `false` if the part returned an error or panicked.
+pub fn run_part<I: Copy, R: PartResult>(
+    func: impl Fn(I) -> R,
+    input: I,
+    day: Day,
+    part: u8,
+) -> bool {
     let part_str = format!("Part {part}");
 
-    let (result, duration, samples) =
-        run_timed(func, input, |result| print_result(result, &part_str, ""));
+    capture_panics();
+
+    let options = BenchOptions::from_args();
+
+    parallel::take_threads_used();
+    let (result, duration, samples, cold_time) = run_timed(func, input, options, |result| {
+        print_result(result, &part_str, "");
+    });
 
-    print_result(&result, &part_str, &format_duration(&duration, samples));
+    let mut duration_str = format_duration(&duration, samples);
+    match parallel::take_threads_used() {
+        0 => {}
+        1 => duration_str.push_str(" (1 thread)"),
+        threads => duration_str.push_str(&format!(" ({threads} threads)")),
+    }
+    if options.is_some_and(|options| options.cold) && samples > 1 {
+        duration_str.push_str(&format!(" (cold: {cold_time:.1?})"));
+    }
 
-    if let Some(result) = result {
+    print_result(&result, &part_str, &duration_str);
+
+    let is_failure = result.is_failure();
+
+    if let PartOutcome::Solved(result) = result {
         submit_result(result, day, part);
     }
+
+    !is_failure
+}
+
+static LAST_PANIC: Mutex<Option<String>> = Mutex::new(None);
+
+/// Set while [`call_part`] runs a part. Not thread-local, so that panics on threads spawned by the
+/// part are captured as well.
+static CAPTURING: AtomicBool = AtomicBool::new(false);
+
+/// Installs a panic hook that records the first panic message and its location while a part runs,
+/// so that it can be reported as the result of the part. Inside a part, the default hook is only
+/// called if backtraces are enabled via `RUST_BACKTRACE`; all other panics are reported by the
+/// default hook as usual.
+fn capture_panics() {
+    static INSTALL: Once = Once::new();
+
+    INSTALL.call_once(|| {
+        let default_hook = panic::take_hook();
+
+        panic::set_hook(Box::new(move |info| {
+            if !CAPTURING.load(Ordering::Relaxed) {
+                default_hook(info);
+                return;
+            }
+
+            let payload = info.payload();
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(ToString::to_string)
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "Box<dyn Any>".into());
+
+            let report = match info.location() {
+                Some(location) => format!("{message} at {location}"),
+                None => message,
+            };
+
+            if let Ok(mut last_panic) = LAST_PANIC.lock() {
+                last_panic.get_or_insert(report);
+            }
+
+            if env::var("RUST_BACKTRACE").is_ok_and(|x| x != "0") {
+                default_hook(info);
+            }
+        }));
+    });
+}
+
+/// Calls `func`, converting an unwinding panic into [`PartOutcome::Panicked`].
+fn call_part<I, R: PartResult>(func: impl Fn(I) -> R, input: I) -> PartOutcome<R::Output> {
+    if let Ok(mut last_panic) = LAST_PANIC.lock() {
+        *last_panic = None;
+    }
+
+    CAPTURING.store(true, Ordering::Relaxed);
+    let outcome = panic::catch_unwind(AssertUnwindSafe(|| func(input).into_outcome()));
+    CAPTURING.store(false, Ordering::Relaxed);
+
+    outcome.unwrap_or_else(|_| {
+        let report = LAST_PANIC
+            .lock()
+            .ok()
+            .and_then(|mut last_panic| last_panic.take())
+            .unwrap_or_else(|| "unknown panic".into());
+        PartOutcome::Panicked(report)
+    })
 }
 
 /// Run a solution part. The behavior differs depending on whether we are running a release or debug build:
 ///  1. in debug, the function is executed once.
 ///  2. in release, the function is benched (approx. 1 second of execution time or 10 samples, whatever take longer.)
-fn run_timed<I: Copy, T>(
-    func: impl Fn(I) -> T,
+///
+/// The first (cold) execution provides the answer and is returned as last value. Parts that fail on
+/// their first execution are not benched.
+fn run_timed<I: Copy, R: PartResult>(
+    func: impl Fn(I) -> R,
     input: I,
-    hook: impl Fn(&T),
-) -> (T, Duration, u128) {
+    options: Option<BenchOptions>,
+    hook: impl Fn(&PartOutcome<R::Output>),
+) -> (PartOutcome<R::Output>, Duration, u128, Duration) {
     let timer = Instant::now();
     let result = {
         #[cfg(feature = "dhat-heap")]
         let _profiler = dhat::Profiler::new_heap();
 
-        func(input)
+        call_part(&func, input)
     };
-    let base_time = timer.elapsed();
+    let cold_time = timer.elapsed();
 
     hook(&result);
 
-    let run = if std::env::args().any(|x| x == "--time") {
-        bench(func, input, &base_time)
+    let run = match options {
+        Some(options) if !result.is_failure() => bench(func, input, &options),
+        _ => (cold_time, 1),
+    };
+
+    (result, run.0, run.1, cold_time)
+}
+
+/// Answer and timing of one implementation of a part, see [`run_variant`].
+pub struct VariantRun {
+    pub name: &'static str,
+    pub part: u8,
+    pub outcome: PartOutcome<String>,
+    pub duration: Duration,
+    pub samples: u128,
+    /// Time of the first execution, if requested with `--cold`.
+    pub cold_time: Option<Duration>,
+}
+
+/// Run and bench one implementation of a part with the [`BenchOptions`] passed to the binary.
+/// Used by `cargo time <day> --variants`.
+pub fn run_variant<I: Copy, R: PartResult>(
+    name: &'static str,
+    part: u8,
+    func: impl Fn(I) -> R,
+    input: I,
+) -> VariantRun {
+    print!("Part {part} · {name}");
+    let _ = stdout().flush();
+
+    let timer = Instant::now();
+    let outcome = match call_part(&func, input) {
+        PartOutcome::Solved(result) => PartOutcome::Solved(result.to_string()),
+        PartOutcome::Unsolved => PartOutcome::Unsolved,
+        PartOutcome::Error(e) => PartOutcome::Error(e),
+        PartOutcome::Panicked(report) => PartOutcome::Panicked(report),
+    };
+    let cold_time = timer.elapsed();
+
+    let options = BenchOptions::from_args().unwrap_or_default();
+    let (duration, samples) = if outcome.is_failure() {
+        (cold_time, 1)
     } else {
-        (base_time, 1)
+        bench(func, input, &options)
     };
 
-    (result, run.0, run.1)
+    println!();
+
+    VariantRun {
+        name,
+        part,
+        outcome,
+        duration,
+        samples,
+        cold_time: (options.cold && samples > 1).then_some(cold_time),
+    }
 }
 
-fn bench<I: Copy, T>(func: impl Fn(I) -> T, input: I, base_time: &Duration) -> (Duration, u128) {
+/// Print a comparison table of variant runs, grouped by part. The first run of each part is the reference.
+/// Returns `false` if any variant failed or disagrees with the reference answer.
+pub fn print_variants(runs: &[VariantRun]) -> bool {
+    let mut success = true;
+
+    let name_width = runs.iter().map(|run| run.name.len()).max().unwrap_or(0);
+    let answer_width = runs
+        .iter()
+        .map(|run| format_outcome(&run.outcome).chars().count())
+        .max()
+        .unwrap_or(0);
+
+    let mut parts: Vec<u8> = runs.iter().map(|run| run.part).collect();
+    parts.sort_unstable();
+    parts.dedup();
+
+    for part in parts {
+        let mut part_runs = runs.iter().filter(|run| run.part == part);
+        let Some(reference) = part_runs.next() else {
+            continue;
+        };
+
+        println!("\n{ANSI_BOLD}Part {part}{ANSI_RESET}");
+
+        for run in std::iter::once(reference).chain(part_runs) {
+            let answer = format_outcome(&run.outcome);
+
+            let comparison = if run.outcome.is_failure() {
+                success = false;
+                String::new()
+            } else if run.outcome != reference.outcome {
+                success = false;
+                format!("✖ disagrees with {}", reference.name)
+            } else if std::ptr::eq(run, reference) {
+                String::new()
+            } else {
+                #[allow(clippy::cast_precision_loss)]
+                let ratio =
+                    run.duration.as_nanos() as f64 / reference.duration.as_nanos().max(1) as f64;
+                format!("{ratio:.2}x")
+            };
+
+            let mut duration = format_duration(&run.duration, run.samples)
+                .trim_start()
+                .to_string();
+            if let Some(cold_time) = run.cold_time {
+                duration.push_str(&format!(" (cold: {cold_time:.1?})"));
+            }
+
+            let line = format!(
+                "  {:name_width$}  {answer:answer_width$} {duration:<28} {comparison}",
+                run.name,
+            );
+            println!("{}", line.trim_end());
+        }
+    }
+
+    success
+}
+
+fn format_outcome(outcome: &PartOutcome<String>) -> String {
+    match outcome {
+        PartOutcome::Solved(result) if result.contains('\n') => "▼".into(),
+        PartOutcome::Solved(result) => result.clone(),
+        PartOutcome::Unsolved => "✖".into(),
+        PartOutcome::Error(e) => format!("✖ {e}"),
+        PartOutcome::Panicked(report) => format!("{PANIC_MARKER} {report}"),
+    }
+}
+
+/// Minimum time spent warming up before calibrating the number of bench iterations.
+const WARMUP_TIME: Duration = Duration::from_millis(100);
+const WARMUP_MAX_RUNS: u32 = 1000;
+
+/// Bench a solution part.
+///  1. warm up caches and branch predictors for approx. 100ms (at least one run).
+///  2. calibrate the number of iterations on the average warm run (approx. 1 second of execution time or 10 samples, whatever take longer.)
+///  3. measure, optionally discarding outliers.
+fn bench<I: Copy, T>(func: impl Fn(I) -> T, input: I, options: &BenchOptions) -> (Duration, u128) {
     let mut stdout = stdout();
 
     print!(" > {ANSI_ITALIC}benching{ANSI_RESET}");
     let _ = stdout.flush();
 
+    trace::silence(true);
+
+    let warmup_timer = Instant::now();
+    let mut warmup_runs = 0;
+    while warmup_runs == 0
+        || (warmup_timer.elapsed() < WARMUP_TIME && warmup_runs < WARMUP_MAX_RUNS)
+    {
+        black_box(func(black_box(input)));
+        warmup_runs += 1;
+    }
+    let warm_time = warmup_timer.elapsed() / warmup_runs;
+
     let bench_iterations =
-        (Duration::from_secs(1).as_nanos() / cmp::max(base_time.as_nanos(), 10)).clamp(10, 10000);
+        (Duration::from_secs(1).as_nanos() / cmp::max(warm_time.as_nanos(), 10)).clamp(10, 10000);
 
     let mut timers: Vec<Duration> = vec![];
 
@@ -67,13 +392,39 @@ fn bench<I: Copy, T>(func: impl Fn(I) -> T, input: I, base_time: &Duration) -> (
         timers.push(timer.elapsed());
     }
 
+    trace::silence(false);
+
+    if options.reject_outliers {
+        timers = reject_outliers(timers);
+    }
+
     (
         #[allow(clippy::cast_possible_truncation)]
         Duration::from_nanos(average_duration(&timers) as u64),
-        bench_iterations,
+        timers.len() as u128,
     )
 }
 
+/// Removes samples outside of the Tukey fences, i.e. more than 1.5 interquartile ranges below the
+/// first or above the third quartile.
+fn reject_outliers(mut timers: Vec<Duration>) -> Vec<Duration> {
+    if timers.len() < 4 {
+        return timers;
+    }
+
+    timers.sort_unstable();
+
+    let q1 = timers[timers.len() / 4];
+    let q3 = timers[timers.len() * 3 / 4];
+    let fence = (q3 - q1) * 3 / 2;
+
+    let low = q1.saturating_sub(fence);
+    let high = q3 + fence;
+
+    timers.retain(|t| (low..=high).contains(t));
+    timers
+}
+
 fn average_duration(numbers: &[Duration]) -> u128 {
     numbers
         .iter()
@@ -90,11 +441,11 @@ fn format_duration(duration: &Duration, samples: u128) -> String {
     }
 }
 
-fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str) {
+fn print_result<T: Display>(result: &PartOutcome<T>, part: &str, duration_str: &str) {
     let is_intermediate_result = duration_str.is_empty();
 
     match result {
-        Some(result) => {
+        PartOutcome::Solved(result) => {
             if result.to_string().contains('\n') {
                 let str = format!("{part}: ▼ {duration_str}");
                 if is_intermediate_result {
@@ -114,7 +465,7 @@ fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str)
                 }
             }
         }
-        None => {
+        PartOutcome::Unsolved => {
             if is_intermediate_result {
                 print!("{part}: ✖");
             } else {
@@ -122,6 +473,22 @@ fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str)
                 println!("{part}: ✖             ");
             }
         }
+        PartOutcome::Error(error) => {
+            if is_intermediate_result {
+                print!("{part}: ✖");
+            } else {
+                print!("\r");
+                println!("{part}: ✖ {error}{duration_str}");
+            }
+        }
+        PartOutcome::Panicked(report) => {
+            if is_intermediate_result {
+                print!("{part}: ✖");
+            } else {
+                print!("\r");
+                println!("{part}: {PANIC_MARKER} {report}");
+            }
+        }
     }
 }
 
@@ -163,3 +530,52 @@ fn submit_result<T: Display>(
     println!("Submitting result via aoc-cli...");
     Some(aoc_cli::submit(day, part, &result.to_string()))
 }
+
+#[cfg(feature = "test_lib")]
+mod tests {
+    use std::time::Duration;
+
+    use super::{call_part, reject_outliers, PartOutcome};
+
+    #[test]
+    fn rejects_outliers() {
+        let mut timers: Vec<Duration> = (100..120).map(Duration::from_nanos).collect();
+        timers.push(Duration::from_nanos(10_000));
+        timers.push(Duration::from_nanos(1));
+
+        let kept = reject_outliers(timers);
+        assert_eq!(kept.len(), 20);
+        assert!(kept
+            .iter()
+            .all(|t| t.as_nanos() >= 100 && t.as_nanos() < 120));
+    }
+
+    #[test]
+    fn keeps_uniform_samples() {
+        let timers = vec![Duration::from_nanos(50); 10];
+        assert_eq!(reject_outliers(timers).len(), 10);
+    }
+
+    #[test]
+    fn converts_part_results() {
+        assert!(matches!(
+            call_part(|x: u32| Some(x), 1),
+            PartOutcome::Solved(1)
+        ));
+        assert!(matches!(
+            call_part(|_: u32| None::<u32>, 1),
+            PartOutcome::Unsolved
+        ));
+        assert!(matches!(
+            call_part(|_: u32| Err::<u32, _>("invalid"), 1),
+            PartOutcome::Error(e) if e == "invalid"
+        ));
+    }
+
+    #[test]
+    fn catches_panics() {
+        let result = call_part(|_: u32| -> Option<u32> { panic!("boom") }, 1);
+        assert!(matches!(result, PartOutcome::Panicked(_)));
+        assert!(result.is_failure());
+    }
+}
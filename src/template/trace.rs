@@ -0,0 +1,80 @@
+//! Debug output for solutions that does not interfere with results or timings.
+//!
+//! Messages are written to stderr and only if the binary was invoked with `--verbose`
+//! (once for [`Level::Debug`], twice for [`Level::Trace`]). They are silenced while benching.
+
+use std::env;
+use std::fmt::Arguments;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+use crate::template::{ANSI_ITALIC, ANSI_RESET};
+
+/// Verbosity level of a message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Debug = 1,
+    Trace = 2,
+}
+
+static SILENCED: AtomicBool = AtomicBool::new(false);
+
+/// Number of times `--verbose` was passed to the current process.
+fn verbosity() -> u8 {
+    static VERBOSITY: OnceLock<u8> = OnceLock::new();
+    *VERBOSITY.get_or_init(|| {
+        let count = env::args().filter(|x| x == "--verbose").count();
+        u8::try_from(count).unwrap_or(u8::MAX)
+    })
+}
+
+/// Returns `true` if messages of `level` are currently printed.
+pub fn enabled(level: Level) -> bool {
+    !is_silenced() && verbosity() >= level as u8
+}
+
+/// Silences all messages and frame dumps until called again with `false`. Used by the runner
+/// while benching.
+pub fn silence(silenced: bool) {
+    SILENCED.store(silenced, Ordering::Relaxed);
+}
+
+/// Returns `true` while output is silenced, see [`silence`].
+pub fn is_silenced() -> bool {
+    SILENCED.load(Ordering::Relaxed)
+}
+
+// Not part of the public API
+#[doc(hidden)]
+pub fn __write(level: Level, args: Arguments) {
+    let prefix = match level {
+        Level::Debug => "debug",
+        Level::Trace => "trace",
+    };
+    eprintln!("{ANSI_ITALIC}{prefix}{ANSI_RESET} {args}");
+}
+
+/// Prints a debug message to stderr if the solution runs with `--verbose`.
+///
+/// Accepts the same arguments as `format!`. Use `level: Trace` as first argument for messages
+/// that should only show up with `--verbose --verbose`.
+///
+/// ```
+/// # let steps = 1;
+/// advent_of_code::trace!("visited {steps} tiles");
+/// advent_of_code::trace!(level: Trace, "queue: {:?}", vec![1, 2]);
+/// ```
+#[macro_export]
+macro_rules! trace {
+    (level: $level:ident, $($arg:tt)+) => {
+        if $crate::template::trace::enabled($crate::template::trace::Level::$level) {
+            $crate::template::trace::__write(
+                $crate::template::trace::Level::$level,
+                format_args!($($arg)+),
+            );
+        }
+    };
+    ($($arg:tt)+) => {
+        $crate::trace!(level: Debug, $($arg)+)
+    };
+}